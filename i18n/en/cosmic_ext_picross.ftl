@@ -1,4 +1,26 @@
 app-title = Picross
 about = About
+appearance = Appearance
+bookmarks = Bookmarks
+best-times = Best times
+statistics = Statistics
+saving = Saving and pausing
+pause = Pause
+restart = Restart puzzle
+undo = Undo
+redo = Redo
+lap = Lap
+whats-new = What's new
 view = View
+progress-in-title = Show progress in title
+crosshair-guides = Crosshair guides
+break-reminders = Suggest breaks after quick losses
+enforce-breaks = Enforce breaks after quick losses
+kid-mode = Kid mode
+lock-kid-mode = Lock kid mode
+large-print = Large print
+stuck-nudges = Nudge when stuck
+save-notification = Notify when saving on close
+streamer-mode = Streamer mode
+stream-overlay = Write stream overlay file
 welcome = Welcome to COSMIC! ✨
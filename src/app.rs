@@ -1,23 +1,115 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 
 use crate::fl;
+use action::MenuAction;
+use chrono::{Datelike, Local, TimeDelta};
 use cosmic::app::{Command, Core};
 use cosmic::iced::alignment::{Horizontal, Vertical};
-use cosmic::iced::{Alignment, Length};
+use cosmic::iced::clipboard;
+use cosmic::iced::keyboard::{self, Key, Modifiers};
+use cosmic::iced::widget::scrollable::{Direction, Properties};
+use cosmic::iced::{event, time, window, Alignment, Event, Font, Length, Size, Subscription};
+use cosmic::widget::menu::action::MenuAction as _;
 use cosmic::widget::{
     self, button, container, menu, mouse_area, text, text_input, Column, Grid, Row, Text,
 };
-use cosmic::{cosmic_theme, theme, Application, ApplicationExt, Apply, Element, Renderer, Theme};
-use game::{pair_to_index, Board, Game, Tile, Winstate};
-use widget_colors::{blacktheme, gray1theme, gray2theme, whitetheme};
+use cosmic::{
+    cosmic_config, cosmic_theme, theme, Application, ApplicationExt, Apply, Element, Renderer,
+    Theme,
+};
+use game::solver::{LineStep, Reason};
+use game::{
+    pair_to_index, Assists, Board, Clues, Game, GameConfig, GameEvent, Line, Solutions,
+    Solvability, Tile, Winstate, ASSIST_PRESETS, MAX_DIMENSION, MAX_FORGIVENESS_SECONDS, MAX_HINTS,
+};
+use mark::{MarkGlyph, MarkStyle};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+pub use setup::DEFAULT_BOARD_SIZE;
+use setup::{Menu, MAX_FIT_TILE_SIZE, MIN_FIT_TILE_SIZE, PRESETS};
+use widget_colors::{
+    blacktheme, gray1theme, gray2theme, guided, orange1theme, progress_fill, solid, stream_border,
+    translucent, whitetheme, BoardColors, BoardPart, Deficiency,
+};
 
+mod action;
+mod challenge;
+mod changelog;
 mod game;
+mod mark;
+mod records;
+mod seasonal;
+mod settings;
+mod setup;
+mod statistics;
+mod storage;
+mod themes;
+mod tilt;
 mod widget_colors;
 
+/// How long the highlight takes to sweep along a line that was just completed.
+const LINE_FLASH_DURATION: Duration = Duration::from_millis(400);
+
+/// How long a game goes without a move before the player is nudged.
+const STUCK_AFTER: Duration = Duration::from_secs(60);
+
+/// Text size of the clock in streamer mode, readable in a scaled down stream.
+const STREAMER_CLOCK_SIZE: u16 = 64;
+
+/// Width of the frame around the playfield in streamer mode.
+const STREAMER_BORDER_WIDTH: f32 = 6.0;
+
+/// File in the data directory that streamer mode writes the clock and progress to.
+const STREAM_OVERLAY_FILE: &str = "stream-overlay.txt";
+
+/// Longest time between saves of the game in progress that can be picked, in minutes.
+const MAX_AUTOSAVE_MINUTES: u8 = 60;
+
+/// Most saves of the game in progress that can be kept.
+const MAX_AUTOSAVES_KEPT: u8 = 10;
+
+/// Longest time without a move before a game pauses itself that can be picked, in
+/// minutes.
+const MAX_IDLE_PAUSE_MINUTES: u8 = 60;
+
+/// How far the wall clock can get ahead between two ticks of the game clock before the
+/// computer is taken to have been asleep in between.
+const SUSPEND_GAP: Duration = Duration::from_secs(30);
+
+/// Largest daily play time budget that can be picked, in minutes.
+const MAX_DAILY_BUDGET_MINUTES: u16 = 600;
+
+/// Minutes the daily play time budget is raised or lowered by at a time.
+const DAILY_BUDGET_STEP: u16 = 15;
+
+/// How long before the daily play time budget runs out the player is warned.
+const BUDGET_WARNING: Duration = Duration::from_secs(5 * 60);
+
+/// Days of play time on the statistics page, today and those before it.
+const PLAY_DAYS_SHOWN: i64 = 7;
+
+/// The most see-through the board chrome can be made, in percent.
+const MIN_BOARD_OPACITY: u8 = 20;
+
 const REPOSITORY: &str = "https://github.com/Kartonrealista/cosmic-ext-picross";
 
+/// The bright board colors of kid mode.
+static KID_COLORS: Lazy<BoardColors> = Lazy::new(|| BoardColors {
+    background: String::from("#fff3b0"),
+    clues: String::from("#ffc6ff"),
+    grid: String::from("#9bf6ff"),
+    ..BoardColors::default()
+});
+
+/// The first field of the start menu, focused whenever the menu is shown.
+static WIDTH_INPUT_ID: Lazy<widget::Id> = Lazy::new(widget::Id::unique);
+
 /// This is the struct that represents your application.
 /// It is used to define the data that will be used by your application.
 pub struct Picross {
@@ -27,7 +119,120 @@ pub struct Picross {
     context_page: ContextPage,
     /// Key bindings for the application's menu bar.
     key_binds: HashMap<menu::KeyBind, MenuAction>,
-    game: Game,
+    menu: Menu,
+    /// The game being played, `None` while the start menu is shown.
+    game: Option<Game>,
+    /// The game last asked for, while its board is still being generated.
+    generating: Option<Generating>,
+    /// Whether the window title shows the progress of the current game.
+    progress_in_title: bool,
+    /// The progress percentage currently shown in the window title.
+    title_progress: Option<u8>,
+    /// Whether the quick settings popover over the board is open.
+    quick_settings_open: bool,
+    /// Name typed for the next bookmark of the current game.
+    bookmark_name: String,
+    /// Label typed for the next lap of the current game.
+    lap_label: String,
+    /// Feedback about the last move, shown under the board until the next one.
+    notice: Option<&'static str>,
+    /// Writes preferences through cosmic-config, `None` if it couldn't be opened.
+    settings_handler: Option<cosmic_config::Config>,
+    /// The preferences as last read or written.
+    settings: Settings,
+    /// Where persisted state is kept, `None` if no data directory could be found.
+    data_dir: Option<PathBuf>,
+    /// Colors picked for the playfield in place of the theme's.
+    board_colors: BoardColors,
+    /// How opaque the clue panels, grid and background are, in percent. Below 100 the
+    /// desktop shows through them.
+    board_opacity: u8,
+    /// Saved board color themes, listed on the appearance page.
+    themes: Vec<themes::BoardTheme>,
+    /// Name the current colors are exported under.
+    theme_name: String,
+    /// Path of a theme file to import.
+    theme_import_path: String,
+    /// Outcome of the last theme export or import.
+    theme_status: Option<String>,
+    /// Break reminders after quick streaks of losses.
+    tilt: tilt::TiltGuard,
+    /// Whether the app shows the simplified, bigger and brighter kid mode.
+    kid_mode: bool,
+    /// Set from the command line, keeping kid mode on until the app is started without it.
+    kid_mode_forced: bool,
+    /// Saved in the settings, keeping kid mode on until it is cleared there from outside
+    /// the app.
+    kid_mode_locked: bool,
+    /// Whether tiles, clues, text and buttons are drawn larger for low-vision players.
+    large_print: bool,
+    /// How marked tiles are drawn.
+    mark_style: MarkStyle,
+    /// Proportions of the tiles on the board.
+    cell_shape: CellShape,
+    /// Font clue numbers are set in.
+    clue_font: ClueFont,
+    /// Whether the player is told how far they got when a game is saved as the window
+    /// closes.
+    save_notification: bool,
+    /// Whether the game in progress is saved, on close and every [`Self::autosave_minutes`].
+    autosaving: bool,
+    /// Minutes between saves of the game in progress, `0` to save only on close.
+    autosave_minutes: u8,
+    /// How many saves are kept, the latest and those before it.
+    autosaves_kept: u8,
+    /// Minutes without a move after which a timed game pauses itself, `0` never to.
+    idle_pause_minutes: u8,
+    /// Whether a desktop notification says a game paused itself.
+    idle_pause_notification: bool,
+    /// Minutes of play a day after which the game in progress pauses itself, `0` for no
+    /// limit.
+    daily_budget_minutes: u16,
+    /// Since when a game has been in play without the time being counted in the
+    /// statistics, or `None` while no game is.
+    playing_since: Option<Instant>,
+    /// Whether a player who stopped making moves is told if line logic still gets them
+    /// further.
+    stuck_nudges: bool,
+    /// When the current game last changed.
+    last_move: Instant,
+    /// When the game clock last ticked, by the monotonic clock and by the wall clock, or
+    /// `None` while it isn't ticking.
+    last_tick: Option<(Instant, SystemTime)>,
+    /// The nudge shown once the current game went [`STUCK_AFTER`] without a move.
+    stuck_nudge: Option<String>,
+    /// The tutor's explanation of the next deduction, with the line it happens in.
+    tutor: Option<(Option<Line>, String)>,
+    /// Lines being highlighted after they were completed, with when that happened.
+    line_flashes: Vec<(Line, Instant)>,
+    /// Whether the playfield is framed for streaming, with a bigger clock and without
+    /// personal records.
+    streamer_mode: bool,
+    /// Whether streamer mode keeps [`STREAM_OVERLAY_FILE`] up to date.
+    stream_overlay: bool,
+    /// Whether guides are drawn through the row and column of the hovered tile.
+    crosshair: bool,
+    /// The tile under the cursor, if any.
+    hovered_tile: Option<usize>,
+    /// The fastest solves of every board size won so far.
+    best_times: Vec<records::BestTimes>,
+    /// Where the time of the game just won placed among the best times of its board.
+    best_time_place: Option<usize>,
+    /// How the time of the game just won compares to earlier wins of its board size.
+    win_comparison: Option<statistics::Comparison>,
+    /// Counts of every game played.
+    statistics: statistics::Statistics,
+    /// Last known size of the main window.
+    window_size: Size,
+}
+
+/// Options given on the command line.
+#[derive(Clone, Debug, Default)]
+pub struct Flags {
+    /// Overrides the directory all persisted state lives in.
+    pub data_dir: Option<PathBuf>,
+    /// Starts in kid mode and keeps it from being turned off.
+    pub kid_mode: bool,
 }
 
 /// This is the enum that contains all the possible variants that your application will need to transmit messages.
@@ -37,14 +242,94 @@ pub struct Picross {
 pub enum Message {
     LaunchUrl(String),
     ToggleContextPage(ContextPage),
+    Key(Modifiers, Key),
+    ApplyPreset(usize),
+    InputPresetName(String),
+    SavePreset,
+    ApplySavedPreset(usize),
+    DeleteSavedPreset(usize),
+    ToggleProgressInTitle,
+    ToggleCrosshair,
+    ToggleBreakReminders,
+    ToggleEnforceBreaks,
+    ToggleKidMode,
+    LockKidMode,
+    ToggleLargePrint,
+    ToggleStuckNudges,
+    ToggleSaveNotification,
+    ToggleAutosave(bool),
+    SetAutosaveMinutes(u8),
+    SetAutosavesKept(u8),
+    AutosaveTick,
+    TogglePause,
+    SetIdlePauseMinutes(u8),
+    ToggleIdlePauseNotification(bool),
+    IdleTick(Instant),
+    SetDailyBudgetMinutes(u16),
+    PlayTick,
+    ToggleStreamerMode,
+    ToggleStreamOverlay,
+    StuckTick(Instant),
+    WindowClosing,
+    /// The preferences were changed outside this window.
+    SettingsChanged(Settings),
+    StartKidGame,
+    ToggleQuickSettings,
+    SetGameAssists(Assists),
+    InputBookmarkName(String),
+    InputNote(String),
+    AddBookmark,
+    RestoreBookmark(usize),
     GotoMenu,
     Reset,
+    Restart,
     Reveal(usize),
+    Undo,
+    Redo,
+    InputLapLabel(String),
+    Lap,
+    Hint,
+    Explain,
     Mark(usize),
     InputHeight(String),
     InputWidth(String),
     InputFilledCount(String),
+    InputSeed(String),
+    CopyShareCode,
+    ShufflePreview,
+    ToggleRelaxed(bool),
+    SetHints(u8),
+    SetAssists(Assists),
+    SetForgiveness(u8),
+    ToggleNoGuessing(bool),
     StartPressed,
+    StartWeekly,
+    SurpriseMe,
+    FitScreen,
+    BoardGenerated(Generating, Board, Solvability),
+    SetFitTileSize(u16),
+    WindowResized(Size),
+    StartSeasonal(usize),
+    InputImportPath(String),
+    ImportFile,
+    PasteGrid,
+    ImportGrid(Option<String>),
+    InputBoardColor(BoardPart, String),
+    SetMarkGlyph(MarkGlyph),
+    InputCustomMark(String),
+    SetMarkSize(u8),
+    SetBoardOpacity(u8),
+    SetCellShape(CellShape),
+    SetClueFont(ClueFont),
+    InputThemeName(String),
+    ExportTheme,
+    InputThemeImportPath(String),
+    ImportTheme,
+    ApplyTheme(usize),
+    FlashTick(Instant),
+    ClockTick,
+    HoverTile(usize),
+    LeaveTile(usize),
 }
 
 /// Identifies a context page to display in the context drawer.
@@ -52,27 +337,24 @@ pub enum Message {
 pub enum ContextPage {
     #[default]
     About,
+    Bookmarks,
+    WhatsNew,
+    Appearance,
+    Saving,
+    BestTimes,
+    Statistics,
 }
 
 impl ContextPage {
     fn title(&self) -> String {
         match self {
             Self::About => fl!("about"),
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum MenuAction {
-    About,
-}
-
-impl menu::action::MenuAction for MenuAction {
-    type Message = Message;
-
-    fn message(&self) -> Self::Message {
-        match self {
-            MenuAction::About => Message::ToggleContextPage(ContextPage::About),
+            Self::Bookmarks => fl!("bookmarks"),
+            Self::WhatsNew => fl!("whats-new"),
+            Self::Appearance => fl!("appearance"),
+            Self::Saving => fl!("saving"),
+            Self::BestTimes => fl!("best-times"),
+            Self::Statistics => fl!("statistics"),
         }
     }
 }
@@ -88,7 +370,7 @@ impl menu::action::MenuAction for MenuAction {
 impl Application for Picross {
     type Executor = cosmic::executor::Default;
 
-    type Flags = ();
+    type Flags = Flags;
 
     type Message = Message;
 
@@ -109,15 +391,93 @@ impl Application for Picross {
     /// - `core` is used to passed on for you by libcosmic to use in the core of your own application.
     /// - `flags` is used to pass in any data that your application needs to use before it starts.
     /// - `Command` type is used to send messages to your application. `Command::none()` can be used to send no messages to your application.
-    fn init(core: Core, _flags: Self::Flags) -> (Self, Command<Self::Message>) {
+    fn init(core: Core, flags: Self::Flags) -> (Self, Command<Self::Message>) {
+        let (settings_handler, settings) = settings::load(Self::APP_ID);
         let mut app = Picross {
             core,
             context_page: ContextPage::default(),
-            key_binds: HashMap::new(),
-            game: Game::new(),
+            key_binds: action::key_binds(),
+            menu: Menu::default(),
+            game: None,
+            generating: None,
+            progress_in_title: false,
+            title_progress: None,
+            quick_settings_open: false,
+            bookmark_name: String::new(),
+            lap_label: String::new(),
+            notice: None,
+            settings_handler,
+            settings: settings.clone(),
+            data_dir: flags
+                .data_dir
+                .or_else(|| dirs::data_dir().map(|dir| dir.join(Self::APP_ID))),
+            board_colors: BoardColors::default(),
+            board_opacity: 100,
+            themes: Vec::new(),
+            theme_name: String::new(),
+            theme_import_path: String::new(),
+            theme_status: None,
+            tilt: tilt::TiltGuard::default(),
+            kid_mode: flags.kid_mode,
+            kid_mode_forced: flags.kid_mode,
+            kid_mode_locked: false,
+            large_print: false,
+            mark_style: MarkStyle::default(),
+            cell_shape: CellShape::default(),
+            clue_font: ClueFont::default(),
+            save_notification: true,
+            autosaving: true,
+            autosave_minutes: 5,
+            autosaves_kept: 1,
+            idle_pause_minutes: 10,
+            idle_pause_notification: false,
+            daily_budget_minutes: 0,
+            playing_since: None,
+            stuck_nudges: true,
+            last_move: Instant::now(),
+            last_tick: None,
+            stuck_nudge: None,
+            tutor: None,
+            line_flashes: Vec::new(),
+            streamer_mode: false,
+            stream_overlay: false,
+            crosshair: false,
+            hovered_tile: None,
+            best_times: Vec::new(),
+            best_time_place: None,
+            win_comparison: None,
+            statistics: statistics::Statistics::default(),
+            window_size: window_size_for_board(
+                DEFAULT_BOARD_SIZE.0,
+                DEFAULT_BOARD_SIZE.1,
+                BoardScale::NORMAL,
+            ),
         };
 
-        let command = app.update_titles();
+        app.apply_board_size(&settings);
+        app.apply_settings(&settings);
+        app.reload_themes();
+        if let Some(path) = app.presets_path() {
+            match setup::load_saved_presets(&path) {
+                Ok(presets) => app.menu.saved_presets = presets,
+                Err(error) => app.menu.preset_error = Some(error),
+            }
+        }
+        if let Some(data_dir) = &app.data_dir {
+            app.best_times = records::load(&records::path(data_dir));
+            app.statistics = statistics::load(&statistics::path(data_dir));
+        }
+        let restored = app.restore_autosave();
+
+        let mut commands = vec![
+            app.update_titles(),
+            widget::text_input::focus(WIDTH_INPUT_ID.clone()),
+            restored,
+        ];
+        if app.record_version_upgrade() {
+            commands.push(app.update(Message::ToggleContextPage(ContextPage::WhatsNew)));
+        }
+        let command = Command::batch(commands);
 
         (app, command)
     }
@@ -126,10 +486,7 @@ impl Application for Picross {
     fn header_start(&self) -> Vec<Element<Self::Message>> {
         let menu_bar = menu::bar(vec![menu::Tree::with_children(
             menu::root(fl!("view")),
-            menu::items(
-                &self.key_binds,
-                vec![menu::Item::Button(fl!("about"), MenuAction::About)],
-            ),
+            menu::items(&self.key_binds, action::menu_items(self)),
         )]);
 
         vec![menu_bar.into()]
@@ -142,10 +499,22 @@ impl Application for Picross {
     ///
     /// To get a better sense of which widgets are available, check out the `widget` module.
     fn view(&self) -> Element<Self::Message> {
-        if self.game.menu.start_pressed {
-            playfield(&self.game)
-        } else {
-            menu(&self.game)
+        #[cfg(feature = "profiling")]
+        profiling::finish_frame!();
+        #[cfg(feature = "profiling")]
+        profiling::scope!("view");
+
+        match &self.game {
+            // Large print boards easily outgrow the window.
+            Some(game) if self.large_print => widget::scrollable(self.game_view(game))
+                .direction(Direction::Both {
+                    vertical: Properties::default(),
+                    horizontal: Properties::default(),
+                })
+                .apply(container),
+            Some(game) => container(self.game_view(game)),
+            None if self.kid_mode => kid_menu(),
+            None => menu(&self.menu, self.notice),
         }
         .apply(widget::container)
         .height(Length::Fill)
@@ -161,61 +530,69 @@ impl Application for Picross {
     /// what message was received. Commands may be returned for asynchronous execution on a
     /// background thread managed by the application's executor.
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
-        match message {
-            Message::LaunchUrl(url) => {
-                let _result = open::that_detached(url);
-            }
-
-            Message::ToggleContextPage(context_page) => {
-                if self.context_page == context_page {
-                    // Close the context drawer if the toggled context page is the same.
-                    self.core.window.show_context = !self.core.window.show_context;
-                } else {
-                    // Open the context drawer to display the requested context page.
-                    self.context_page = context_page;
-                    self.core.window.show_context = true;
-                }
-
-                // Set the title of the context drawer.
-                self.set_context_title(context_page.title());
-            }
-            Message::Reveal(id) => {
-                self.game.board.board_vec[id].hidden = false;
-                self.game.wincheck();
-            }
-            Message::Mark(id) => {
-                let marked = &mut self.game.board.board_vec[id].marked;
-                *marked = !*marked;
-                self.game.wincheck()
-            }
-            Message::GotoMenu => {
-                self.game = Game::new();
-            }
-            Message::InputWidth(input) => self.game.menu.width_input = input,
-            Message::InputHeight(input) => self.game.menu.height_input = input,
-            Message::InputFilledCount(input) => self.game.menu.filled_count_input = input,
-            Message::StartPressed => {
-                self.game.board.width = self.game.menu.width_input.parse().unwrap();
-                self.game.board.height = self.game.menu.height_input.parse().unwrap();
-                self.game.board.filled_count = self.game.menu.filled_count_input.parse().unwrap();
-                self.game.board = Board::new(
-                    self.game.board.width,
-                    self.game.board.height,
-                    self.game.board.filled_count,
-                );
-                self.game.menu.start_pressed = true;
-            }
+        let command = self.handle_message(message);
+        match (self.playing_since, self.playing()) {
+            (None, true) => self.playing_since = Some(Instant::now()),
+            (Some(_), false) => self.count_play_time(),
+            _ => {}
+        }
+        if !self.clock_ticking() {
+            // A tick after the clock starts again is measured from then, not from
+            // before it stopped.
+            self.last_tick = None;
+        }
+        self.store_settings();
+        #[cfg(debug_assertions)]
+        self.check_integrity();
+        command
+    }
 
-            Message::Reset => {
-                self.game.board = Board::new(
-                    self.game.board.width,
-                    self.game.board.height,
-                    self.game.board.filled_count,
-                );
-                self.game.winstate = Winstate::InProgress;
-            }
+    fn subscription(&self) -> Subscription<Self::Message> {
+        let mut subscriptions = vec![
+            keyboard::on_key_press(|key, modifiers| Some(Message::Key(modifiers, key))),
+            event::listen_with(|event, _status| match event {
+                Event::Window(_id, window::Event::Resized { width, height }) => Some(
+                    Message::WindowResized(Size::new(width as f32, height as f32)),
+                ),
+                _ => None,
+            }),
+        ];
+        if !self.line_flashes.is_empty() {
+            subscriptions.push(time::every(Duration::from_millis(16)).map(Message::FlashTick));
+        }
+        let in_progress = self
+            .game
+            .as_ref()
+            .is_some_and(|game| matches!(game.winstate, Winstate::InProgress));
+        let paused = self.game.as_ref().is_some_and(|game| game.paused);
+        if self.clock_ticking() {
+            // Redraws and looks out for the computer waking up, the clock itself keeps
+            // time.
+            subscriptions.push(time::every(Duration::from_secs(1)).map(|_| Message::ClockTick));
         }
-        Command::none()
+        if self.clock_ticking() && self.idle_pause_minutes > 0 {
+            subscriptions.push(time::every(Duration::from_secs(15)).map(Message::IdleTick));
+        }
+        if self.playing() {
+            subscriptions.push(time::every(Duration::from_secs(60)).map(|_| Message::PlayTick));
+        }
+        if self.autosaving && self.autosave_minutes > 0 && in_progress {
+            let every = Duration::from_secs(60 * u64::from(self.autosave_minutes));
+            subscriptions.push(time::every(every).map(|_| Message::AutosaveTick));
+        }
+        if self.stuck_nudges && self.stuck_nudge.is_none() && in_progress && !paused {
+            subscriptions.push(time::every(Duration::from_secs(5)).map(Message::StuckTick));
+        }
+        subscriptions.push(
+            self.core()
+                .watch_config::<Settings>(Self::APP_ID)
+                .map(|update| Message::SettingsChanged(update.config)),
+        );
+        Subscription::batch(subscriptions)
+    }
+
+    fn on_close_requested(&self, _id: window::Id) -> Option<Self::Message> {
+        Some(Message::WindowClosing)
     }
 
     /// Display a context drawer if the context page is requested.
@@ -226,6 +603,12 @@ impl Application for Picross {
 
         Some(match self.context_page {
             ContextPage::About => self.about(),
+            ContextPage::Bookmarks => self.bookmarks(),
+            ContextPage::WhatsNew => self.whats_new(),
+            ContextPage::Appearance => self.appearance(),
+            ContextPage::Saving => self.saving_page(),
+            ContextPage::BestTimes => self.best_times_page(),
+            ContextPage::Statistics => self.statistics_page(),
         })
     }
 }
@@ -247,197 +630,2109 @@ impl Picross {
             .on_press(Message::LaunchUrl(REPOSITORY.to_string()))
             .padding(0);
 
+        let data_dir = text::caption(match &self.data_dir {
+            Some(dir) => format!("Data: {}", dir.display()),
+            None => String::from("No data directory"),
+        });
+
         widget::column()
             .push(icon)
             .push(title)
             .push(link)
+            .push(data_dir)
             .align_items(Alignment::Center)
             .spacing(space_xxs)
             .into()
     }
 
-    /// Updates the header and window titles.
-    pub fn update_titles(&mut self) -> Command<Message> {
-        let window_title = fl!("app-title");
-        let header_title = String::new();
+    /// Starts a game on a premade board with the options chosen in the menu.
+    fn start_with_board(&mut self, board: Board) {
+        match self.menu.config_for_board(&board) {
+            Ok(config) => {
+                self.menu.import_error = None;
+                self.start_game(Game::with_board(config, board));
+            }
+            Err(error) => self.menu.import_error = Some(error.to_string()),
+        }
+    }
 
-        self.set_header_title(header_title);
-        self.set_window_title(window_title)
+    fn import_grid(&mut self, input: &str) {
+        match Board::parse_grid(input) {
+            Ok(board) => {
+                let ambiguous = matches!(board.solutions(), Solutions::Multiple(..));
+                self.start_with_board(board);
+                if ambiguous && self.game.is_some() {
+                    self.notice = Some(
+                        "This puzzle has more than one solution, so some guesses may not pay off",
+                    );
+                }
+            }
+            Err(error) => self.menu.import_error = Some(error.to_string()),
+        }
     }
-}
 
-fn playfield(game: &Game) -> widget::Container<'_, Message, cosmic::Theme> {
-    let disabled_tilebutton = |id: usize| match game.board.board_vec[id] {
-        Tile {
-            hidden: true,
-            marked: true,
-            ..
-        } => mouse_area(
-            container(centralize_tile_content(text(String::from("X")).size(25)))
-                .style(theme::Container::Secondary)
-                .center_x()
-                .center_y()
-                .height(50)
-                .width(50),
-        ),
-        Tile {
-            hidden: true,
-            marked: false,
-            ..
-        } => mouse_area(
-            container("")
-                .style(theme::Container::Secondary)
-                .center_x()
-                .center_y()
-                .height(50)
-                .width(50),
-        ),
-        Tile {
-            hidden: false,
-            empty: true,
-            ..
-        } => mouse_area(
-            container("")
-                .style(theme::Container::custom(gray1theme))
-                .center_x()
-                .center_y()
-                .height(50)
-                .width(50),
-        ),
-        Tile {
-            hidden: false,
-            empty: false,
-            ..
-        } => mouse_area(
-            container("")
-                .style(theme::Container::custom(blacktheme))
-                .center_x()
-                .center_y()
-                .height(50)
-                .width(50),
-        ),
-    };
-    let tilebutton = |id: usize| match game.winstate {
-        Winstate::Won => disabled_tilebutton(id),
-        Winstate::Lost => disabled_tilebutton(id),
-        Winstate::InProgress => disabled_tilebutton(id)
-            .on_press(Message::Reveal(id))
-            .on_right_press(Message::Mark(id)),
-    };
-    let playboard = (0..game.board.height).fold(Grid::new(), |acc, row| {
-        let new_row = (0..game.board.width).fold(Row::new(), |acc2, column| {
-            acc2.push(tilebutton(pair_to_index(row, column, game.board.width)))
-        });
-        acc.push(new_row.spacing(2).align_items(Alignment::Center))
-            .insert_row()
-    });
-    let menu_button = button("Menu")
-        .on_press(Message::GotoMenu)
-        .style(theme::Button::Suggested);
-    let reset_button = button("Reset")
-        .on_press(Message::Reset)
-        .style(theme::Button::Destructive);
-    let winstate_text = match game.winstate {
-        Winstate::Won => "You won!",
-        Winstate::Lost => "You lost!",
-        Winstate::InProgress => "Game in progress...",
-    };
-    let vertical_count_column = |vec: &Vec<usize>| {
-        vec.iter()
-            .fold(Column::new(), |acc: Column<'_, Message>, count| {
-                acc.push(
-                    container(centralize_tile_content(text(format!("{}", count))))
-                        .height(20)
-                        .center_x()
-                        .center_y(),
-                )
+    /// The fastest solves of every board size, or how to get some.
+    pub fn best_times_page(&self) -> Element<Message> {
+        let cosmic_theme::Spacing { space_s, .. } = theme::active().cosmic().spacing;
+
+        if self.best_times.is_empty() {
+            return text("Win a game that isn't relaxed to set a best time.").into();
+        }
+        self.best_times
+            .iter()
+            .fold(widget::column(), |acc, best| {
+                acc.push(best_times_list(best, None))
             })
-    };
-    let vertical_counts = (&game)
-        .board
-        .vertical_count
-        .iter()
-        .fold(Row::new(), |acc, column| {
-            acc.push(
-                container(vertical_count_column(column).align_items(Alignment::Center))
-                    .style(theme::Container::Primary)
-                    .width(50)
-                    .center_x()
-                    .center_y(),
-            )
+            .spacing(space_s)
+            .into()
+    }
+
+    /// Fills the start menu's board size from `settings`.
+    fn apply_board_size(&mut self, settings: &Settings) {
+        self.menu.width_input = settings.width.to_string();
+        self.menu.height_input = settings.height.to_string();
+        self.menu.filled_count_input = settings.filled_count.to_string();
+        self.menu.refresh_preview();
+    }
+
+    /// Takes over every preference in `settings` but the board size.
+    fn apply_settings(&mut self, settings: &Settings) {
+        self.menu.relaxed = settings.relaxed;
+        self.menu.hints = settings.hints.min(MAX_HINTS);
+        self.menu.assists = settings.assists;
+        self.menu.forgiveness_seconds = settings.forgiveness_seconds.min(MAX_FORGIVENESS_SECONDS);
+        self.menu.no_guessing = settings.no_guessing;
+        self.progress_in_title = settings.progress_in_title;
+        self.crosshair = settings.crosshair;
+        self.tilt.remind = settings.break_reminders;
+        self.tilt.enforce = settings.enforce_breaks;
+        self.large_print = settings.large_print;
+        self.stuck_nudges = settings.stuck_nudges;
+        self.save_notification = settings.save_notification;
+        self.autosaving = settings.autosave;
+        self.autosave_minutes = settings.autosave_minutes.min(MAX_AUTOSAVE_MINUTES);
+        self.autosaves_kept = settings.autosaves_kept.clamp(1, MAX_AUTOSAVES_KEPT);
+        self.idle_pause_minutes = settings.idle_pause_minutes.min(MAX_IDLE_PAUSE_MINUTES);
+        self.idle_pause_notification = settings.idle_pause_notification;
+        self.daily_budget_minutes = settings.daily_budget_minutes.min(MAX_DAILY_BUDGET_MINUTES);
+        self.streamer_mode = settings.streamer_mode;
+        self.stream_overlay = settings.stream_overlay;
+        self.board_opacity = settings.board_opacity.clamp(MIN_BOARD_OPACITY, 100);
+        self.clue_font = settings.clue_font;
+        self.board_colors = settings.board_colors.clone();
+        self.mark_style = MarkStyle {
+            size: settings
+                .mark_style
+                .size
+                .clamp(mark::MIN_SIZE, mark::MAX_SIZE),
+            ..settings.mark_style.clone()
+        };
+        self.cell_shape = settings.cell_shape;
+        self.kid_mode_locked = settings.kid_mode_locked;
+        // Kid mode forced on from the command line stays on whatever was saved, and a lock
+        // keeps it on.
+        if !self.kid_mode_forced {
+            self.kid_mode = settings.kid_mode || settings.kid_mode_locked;
+        }
+    }
+
+    /// The preferences as they are now. A board size that isn't a number yet keeps the
+    /// last one that was.
+    fn current_settings(&self) -> Settings {
+        let number = |input: &str, previous: usize| input.trim().parse().unwrap_or(previous);
+        Settings {
+            width: number(&self.menu.width_input, self.settings.width),
+            height: number(&self.menu.height_input, self.settings.height),
+            filled_count: number(&self.menu.filled_count_input, self.settings.filled_count),
+            relaxed: self.menu.relaxed,
+            hints: self.menu.hints,
+            assists: self.menu.assists,
+            forgiveness_seconds: self.menu.forgiveness_seconds,
+            no_guessing: self.menu.no_guessing,
+            progress_in_title: self.progress_in_title,
+            crosshair: self.crosshair,
+            break_reminders: self.tilt.remind,
+            enforce_breaks: self.tilt.enforce,
+            large_print: self.large_print,
+            stuck_nudges: self.stuck_nudges,
+            save_notification: self.save_notification,
+            autosave: self.autosaving,
+            autosave_minutes: self.autosave_minutes,
+            autosaves_kept: self.autosaves_kept,
+            idle_pause_minutes: self.idle_pause_minutes,
+            idle_pause_notification: self.idle_pause_notification,
+            daily_budget_minutes: self.daily_budget_minutes,
+            streamer_mode: self.streamer_mode,
+            stream_overlay: self.stream_overlay,
+            board_opacity: self.board_opacity,
+            clue_font: self.clue_font,
+            board_colors: self.board_colors.clone(),
+            mark_style: self.mark_style.clone(),
+            cell_shape: self.cell_shape,
+            // Only the player's own choice is saved, not one forced from the command line.
+            kid_mode: if self.kid_mode_forced {
+                self.settings.kid_mode
+            } else {
+                self.kid_mode
+            },
+            kid_mode_locked: self.kid_mode_locked,
+        }
+    }
+
+    /// Writes the preferences through cosmic-config if a message changed them.
+    fn store_settings(&mut self) {
+        let current = self.current_settings();
+        if current == self.settings {
+            return;
+        }
+        if let Some(handler) = &self.settings_handler {
+            // Left unsaved, the preferences still apply until the app is closed.
+            let _result = current.write_entry(handler);
+        }
+        self.settings = current;
+    }
+
+    /// Every game played, counted by how it ended.
+    pub fn statistics_page(&self) -> Element<Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+
+        let statistics = &self.statistics;
+        let ended = statistics.won + statistics.lost + statistics.abandoned;
+        let win_rate = if ended == 0 {
+            String::from("-")
+        } else {
+            format!("{}%", statistics.won * 100 / ended)
+        };
+        let average = statistics
+            .average_solve_time()
+            .map_or_else(|| String::from("-"), format_clock);
+        let row = |name: String, value: String| {
+            widget::row()
+                .push(text(name).width(Length::Fill))
+                .push(text(value))
+        };
+        let today = Local::now().date_naive();
+        let play_days = (0..PLAY_DAYS_SHOWN).map(|back| {
+            let date = today - TimeDelta::days(back);
+            let played = format_play_time(statistics.played_on(date.num_days_from_ce()));
+            match back {
+                0 if self.daily_budget_minutes > 0 => {
+                    let budget = Duration::from_secs(60 * u64::from(self.daily_budget_minutes));
+                    (
+                        String::from("Today"),
+                        format!("{played} of {}", format_play_time(budget)),
+                    )
+                }
+                0 => (String::from("Today"), played),
+                1 => (String::from("Yesterday"), played),
+                _ => (date.format("%A %-d %B").to_string(), played),
+            }
         });
-    let horizontal_count_row = |vec: &Vec<usize>| {
-        vec.iter().fold(Row::new(), |acc: Row<'_, Message>, count| {
-            acc.push(
-                container(centralize_tile_content(text(format!("{}", count))))
-                    .width(20)
-                    .center_x()
-                    .center_y(),
+        let counts = [
+            ("Games started", statistics.started.to_string()),
+            ("Won", statistics.won.to_string()),
+            ("Lost", statistics.lost.to_string()),
+            ("Abandoned", statistics.abandoned.to_string()),
+            ("Win rate", win_rate),
+            ("Average solve time", average),
+            ("Current win streak", statistics.current_streak.to_string()),
+            ("Longest win streak", statistics.longest_streak.to_string()),
+        ]
+        .into_iter()
+        .fold(widget::column(), |acc, (name, value)| {
+            acc.push(row(name.to_string(), value))
+        });
+        play_days
+            .fold(
+                counts.push(widget::text::title4("Play time")),
+                |acc, (name, value)| acc.push(row(name, value)),
             )
-        })
-    };
-    let horizontal_counts =
-        (&game)
-            .board
-            .horizontal_count
+            .spacing(space_xxs)
+            .into()
+    }
+
+    /// Generates the board of a game with `config` off the UI thread, which can take the
+    /// solver a while, and starts the game with [`Message::BoardGenerated`] once it's done.
+    fn generate_game(
+        &mut self,
+        config: GameConfig,
+        seed: u64,
+        tile_size: Option<u16>,
+    ) -> Command<Message> {
+        let generating = Generating {
+            config,
+            seed,
+            tile_size,
+        };
+        self.generating = Some(generating);
+        self.notice = Some("Generating the board…");
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    Board::for_config(&generating.config, generating.seed)
+                })
+                .await
+                .expect("generating a board doesn't panic")
+            },
+            move |(board, solvability)| {
+                cosmic::app::Message::App(Message::BoardGenerated(generating, board, solvability))
+            },
+        )
+    }
+
+    /// Replaces the current game with `game`, counting both in the statistics.
+    fn start_game(&mut self, game: Game) {
+        // Whatever was still being generated has been superseded.
+        self.generating = None;
+        self.abandon_game();
+        self.count_game(statistics::Outcome::Started);
+        self.game = Some(game);
+        self.notice_solvability();
+    }
+
+    /// Warns when the current game's board falls short of what generation was asked
+    /// for: a single solution, or with no guessing one line logic alone finds.
+    fn notice_solvability(&mut self) {
+        self.notice = match &self.game {
+            Some(game) if !game.fixed => match game.solvability {
+                Solvability::Unproven => {
+                    Some("This board may have several solutions, so some guesses may not pay off")
+                }
+                Solvability::Unique if game.config.no_guessing => {
+                    Some("Line logic alone won't solve this board, but it has a single solution")
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+    }
+
+    /// Counts the current game as abandoned if it is still in play and has no other
+    /// outcome yet.
+    fn abandon_game(&mut self) {
+        if self.game.as_ref().is_some_and(|game| {
+            matches!(game.winstate, Winstate::InProgress) && !game.outcome_counted
+        }) {
+            self.count_game(statistics::Outcome::Abandoned);
+        }
+    }
+
+    /// Marks the current game's outcome as counted, returning whether it was the first.
+    fn settle_game(&mut self) -> bool {
+        self.game
+            .as_mut()
+            .is_some_and(|game| !std::mem::replace(&mut game.outcome_counted, true))
+    }
+
+    fn count_game(&mut self, outcome: statistics::Outcome) {
+        match &self.data_dir {
+            Some(data_dir) => match statistics::record(&statistics::path(data_dir), outcome) {
+                Ok(statistics) => self.statistics = statistics,
+                // Still counted for as long as the app runs.
+                Err(_) => self.statistics.count(outcome),
+            },
+            None => self.statistics.count(outcome),
+        }
+    }
+
+    /// Adds the time of the game just won to the best times of its board size.
+    fn record_best_time(&mut self) {
+        let (Some(game), Some(data_dir)) = (&self.game, &self.data_dir) else {
+            return;
+        };
+        if game.config.relaxed {
+            return;
+        }
+        match records::record(
+            &records::path(data_dir),
+            &game.config,
+            game.clock.elapsed(),
+            game.hints_used,
+        ) {
+            Ok((best_times, place)) => {
+                self.best_times = best_times;
+                self.best_time_place = place;
+            }
+            Err(_) => self.notice = Some("The time couldn't be saved among the best times"),
+        }
+    }
+
+    /// Release notes, with buttons leading to the features they mention.
+    pub fn whats_new(&self) -> Element<Message> {
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+
+        changelog::RELEASES
             .iter()
-            .fold(Column::new(), |acc, row| {
-                acc.push(
-                    container(horizontal_count_row(row).align_items(Alignment::Center))
-                        .style(theme::Container::Primary)
-                        .height(50)
-                        .center_x()
-                        .center_y(),
-                )
-            });
+            .fold(widget::column(), |acc, release| {
+                let changes = release.changes.iter().fold(
+                    widget::column().push(widget::text::title4(release.version)),
+                    |acc, change| {
+                        let row = widget::row()
+                            .push(text(format!("\u{2022} {}", change.text)).width(Length::Fill));
+                        let row = match change.link {
+                            // Following a link to the menu would abandon the current game.
+                            Some(changelog::ChangeLink::StartMenu) if self.game.is_some() => row,
+                            Some(link) => row.push(button::link("Show").on_press(match link {
+                                changelog::ChangeLink::Page(page) => {
+                                    Message::ToggleContextPage(page)
+                                }
+                                changelog::ChangeLink::StartMenu => Message::GotoMenu,
+                            })),
+                            None => row,
+                        };
+                        acc.push(row.align_items(Alignment::Center))
+                    },
+                );
+                acc.push(changes.spacing(space_xxs))
+            })
+            .spacing(space_s)
+            .into()
+    }
 
-    container(
-        widget::column()
+    /// Remembers the version being run, returning whether it differs from the one
+    /// that ran last time.
+    fn record_version_upgrade(&self) -> bool {
+        let Some(data_dir) = &self.data_dir else {
+            return false;
+        };
+        storage::update_locked(&data_dir.join("version"), |previous| {
+            let upgraded =
+                previous.is_some_and(|previous| previous.trim() != changelog::CURRENT_VERSION);
+            Ok((changelog::CURRENT_VERSION.to_string(), upgraded))
+        })
+        .unwrap_or(false)
+    }
+
+    /// Saved snapshots of the current game, with a field for adding new ones.
+    pub fn bookmarks(&self) -> Element<Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+
+        let Some(game) = &self.game else {
+            return text("Start a game to bookmark its board.").into();
+        };
+
+        let add_row = widget::row()
             .push(
-                container(
-                    container(vertical_counts.spacing(2).align_items(Alignment::End))
-                        .style(theme::Container::Primary)
-                        .height((((game.board.height + 1) / 2) * 20) as u16)
-                        .center_x()
-                        .align_y(Vertical::Bottom),
-                )
-                .style(theme::Container::Primary)
-                .align_x(Horizontal::Right)
-                .width((52 * game.board.width + 2) as f32)
-                .center_x()
-                .center_y()
-                .padding(0),
+                text_input("Bookmark name", &self.bookmark_name)
+                    .on_input(Message::InputBookmarkName)
+                    .on_submit(Message::AddBookmark),
             )
-            .push(
+            .push(button("Add").on_press(Message::AddBookmark))
+            .align_items(Alignment::Center)
+            .spacing(space_xxs);
+
+        game.bookmarks
+            .iter()
+            .enumerate()
+            .fold(
                 widget::column()
+                    .push(text("Note"))
                     .push(
+                        text_input("Notes about this puzzle", &game.note)
+                            .on_input(Message::InputNote),
+                    )
+                    .push(add_row),
+                |acc, (index, bookmark)| {
+                    acc.push(
                         widget::row()
-                            .push(
-                                container(
-                                    container(
-                                        horizontal_counts.spacing(2).align_items(Alignment::End),
+                            .push(text(&bookmark.name).width(Length::Fill))
+                            .push(button("Restore").on_press(Message::RestoreBookmark(index)))
+                            .align_items(Alignment::Center),
+                    )
+                },
+            )
+            .spacing(space_xxs)
+            .into()
+    }
+
+    /// When and how many times the game in progress is saved, and when it pauses itself.
+    pub fn saving_page(&self) -> Element<Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+
+        let interval = match self.autosave_minutes {
+            0 => String::from("Only on close"),
+            1 => String::from("Every minute"),
+            minutes => format!("Every {minutes} minutes"),
+        };
+        let kept = match self.autosaves_kept {
+            1 => String::from("Keep the latest save"),
+            kept => format!("Keep the latest {kept} saves"),
+        };
+        let idle = match self.idle_pause_minutes {
+            0 => String::from("Never pause idle games"),
+            1 => String::from("Pause after a minute without a move"),
+            minutes => format!("Pause after {minutes} minutes without a move"),
+        };
+        let budget = match self.daily_budget_minutes {
+            0 => String::from("No daily limit"),
+            minutes => format!(
+                "Pause after {} of play a day",
+                format_play_time(Duration::from_secs(60 * u64::from(minutes)))
+            ),
+        };
+        let autosaving = self.autosaving;
+        let stepper = |label: String, minus: Message, plus: Message, enabled: bool| {
+            widget::row()
+                .push(button("-").on_press_maybe(enabled.then_some(minus)))
+                .push(button("+").on_press_maybe(enabled.then_some(plus)))
+                .push(text(label))
+                .align_items(Alignment::Center)
+                .spacing(space_xxs)
+        };
+        widget::column()
+            .push(widget::text::title4("Autosave"))
+            .push(widget::checkbox(
+                "Save the game in progress",
+                autosaving,
+                Message::ToggleAutosave,
+            ))
+            .push(stepper(
+                interval,
+                Message::SetAutosaveMinutes(self.autosave_minutes.saturating_sub(1)),
+                Message::SetAutosaveMinutes(self.autosave_minutes + 1),
+                autosaving,
+            ))
+            .push(stepper(
+                kept,
+                Message::SetAutosavesKept(self.autosaves_kept.saturating_sub(1)),
+                Message::SetAutosavesKept(self.autosaves_kept + 1),
+                autosaving,
+            ))
+            .push(widget::text::caption(
+                "Earlier saves are kept beside the latest as autosave.toml.1, .2 and so on. \
+                 With saving off, no file is written and no game is restored.",
+            ))
+            .push(widget::text::title4("Pausing"))
+            .push(stepper(
+                idle,
+                Message::SetIdlePauseMinutes(self.idle_pause_minutes.saturating_sub(1)),
+                Message::SetIdlePauseMinutes(self.idle_pause_minutes + 1),
+                true,
+            ))
+            .push(widget::checkbox(
+                "Notify when a game pauses itself",
+                self.idle_pause_notification,
+                Message::ToggleIdlePauseNotification,
+            ))
+            .push(widget::text::caption(
+                "A timed game left without a move pauses itself, and the time it sat \
+                 untouched is taken back off its clock. It pauses too when the computer \
+                 goes to sleep, none of the time asleep counting.",
+            ))
+            .push(widget::text::title4("Daily play time"))
+            .push(stepper(
+                budget,
+                Message::SetDailyBudgetMinutes(
+                    self.daily_budget_minutes.saturating_sub(DAILY_BUDGET_STEP),
+                ),
+                Message::SetDailyBudgetMinutes(self.daily_budget_minutes + DAILY_BUDGET_STEP),
+                true,
+            ))
+            .push(widget::text::caption(
+                "Play time adds up over every window and launch of the day. You're told \
+                 when five minutes are left, and once they're up the game pauses itself. \
+                 The statistics show how long you played each day.",
+            ))
+            .spacing(space_xxs)
+            .into()
+    }
+
+    /// Color pickers for the playfield, applied to the board as they are typed.
+    pub fn appearance(&self) -> Element<Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+
+        let pickers = BoardPart::ALL.into_iter().fold(
+            widget::column().push(text("Colors as #rrggbb, leave empty to follow the theme.")),
+            |acc, part| {
+                let input = self.board_colors.input(part);
+                let swatch = container("").width(24).height(24);
+                let swatch = match self.board_colors.color(part) {
+                    Some(color) => swatch.style(solid(color)),
+                    None => swatch,
+                };
+                let column = widget::column().push(text(part.name())).push(
+                    widget::row()
+                        .push(
+                            text_input("Theme default", input)
+                                .on_input(move |input| Message::InputBoardColor(part, input)),
+                        )
+                        .push(swatch)
+                        .align_items(Alignment::Center)
+                        .spacing(space_xxs),
+                );
+                acc.push(
+                    if input.trim().is_empty() || self.board_colors.color(part).is_some() {
+                        column
+                    } else {
+                        column.push(widget::text::caption("Not a #rrggbb color"))
+                    },
+                )
+            },
+        );
+        let glyphs = MarkGlyph::ALL
+            .into_iter()
+            .fold(widget::row(), |acc, glyph| {
+                acc.push(
+                    button(glyph.name())
+                        .on_press(Message::SetMarkGlyph(glyph))
+                        .style(if self.mark_style.glyph == glyph {
+                            theme::Button::Suggested
+                        } else {
+                            theme::Button::Standard
+                        }),
+                )
+            })
+            .push(
+                text_input("Character", &self.mark_style.custom)
+                    .on_input(Message::InputCustomMark)
+                    .width(Length::Fixed(100.0)),
+            )
+            .align_items(Alignment::Center)
+            .spacing(space_xxs);
+        let mark_size = widget::row()
+            .push(text("Size"))
+            .push(button("-").on_press(Message::SetMarkSize(
+                self.mark_style.size.saturating_sub(10),
+            )))
+            .push(text(format!("{}%", self.mark_style.size)))
+            .push(button("+").on_press(Message::SetMarkSize(self.mark_style.size + 10)))
+            .push(
+                container(text(self.mark_style.text()).size(self.mark_style.font_size(50)))
+                    .style(theme::Container::Secondary)
+                    .center_x()
+                    .center_y()
+                    .width(50)
+                    .height(50),
+            )
+            .align_items(Alignment::Center)
+            .spacing(space_xxs);
+        let board_opacity = widget::row()
+            .push(text("Board opacity"))
+            .push(button("-").on_press(Message::SetBoardOpacity(
+                self.board_opacity.saturating_sub(10),
+            )))
+            .push(text(format!("{}%", self.board_opacity)))
+            .push(button("+").on_press(Message::SetBoardOpacity(self.board_opacity + 10)))
+            .align_items(Alignment::Center)
+            .spacing(space_xxs);
+        let cell_shapes = CellShape::ALL
+            .into_iter()
+            .fold(widget::row(), |acc, shape| {
+                acc.push(
+                    button(shape.name())
+                        .on_press(Message::SetCellShape(shape))
+                        .style(if self.cell_shape == shape {
+                            theme::Button::Suggested
+                        } else {
+                            theme::Button::Standard
+                        }),
+                )
+            })
+            .spacing(space_xxs);
+        let clue_fonts = ClueFont::ALL
+            .into_iter()
+            .fold(widget::row(), |acc, font| {
+                acc.push(
+                    button(font.name())
+                        .on_press(Message::SetClueFont(font))
+                        .style(if self.clue_font == font {
+                            theme::Button::Suggested
+                        } else {
+                            theme::Button::Standard
+                        }),
+                )
+            })
+            .spacing(space_xxs);
+        let export_row = widget::row()
+            .push(
+                text_input("Theme name", &self.theme_name)
+                    .on_input(Message::InputThemeName)
+                    .on_submit(Message::ExportTheme),
+            )
+            .push(button("Export").on_press(Message::ExportTheme))
+            .align_items(Alignment::Center)
+            .spacing(space_xxs);
+        let import_row = widget::row()
+            .push(
+                text_input("Path to a .toml theme", &self.theme_import_path)
+                    .on_input(Message::InputThemeImportPath)
+                    .on_submit(Message::ImportTheme),
+            )
+            .push(button("Import").on_press(Message::ImportTheme))
+            .align_items(Alignment::Center)
+            .spacing(space_xxs);
+        let palette = widget_colors::palette(&self.board_colors);
+        let pickers = Deficiency::ALL.into_iter().fold(
+            pickers.push(widget::text::title4("Color vision preview")),
+            |acc, deficiency| {
+                let swatches = palette.iter().fold(widget::row(), |acc, &color| {
+                    acc.push(
+                        container("")
+                            .width(24)
+                            .height(24)
+                            .style(solid(deficiency.simulate(color))),
+                    )
+                });
+                acc.push(
+                    widget::row()
+                        .push(text(deficiency.name()).width(Length::Fixed(120.0)))
+                        .push(swatches.spacing(space_xxs))
+                        .align_items(Alignment::Center),
+                )
+            },
+        );
+        let pickers = pickers
+            .push(board_opacity)
+            .push(widget::text::caption(
+                "Below 100% the desktop shows through the board where the compositor allows it.",
+            ))
+            .push(widget::text::title4("Cell shape"))
+            .push(cell_shapes)
+            .push(widget::text::title4("Clue font"))
+            .push(clue_fonts)
+            .push(widget::text::title4("Marks"))
+            .push(glyphs)
+            .push(mark_size)
+            .push(widget::text::title4("Themes"))
+            .push(export_row)
+            .push(import_row)
+            .push(widget::text::caption(
+                self.theme_status.as_deref().unwrap_or_default(),
+            ));
+        self.themes
+            .iter()
+            .enumerate()
+            .fold(pickers, |acc, (index, theme)| {
+                acc.push(
+                    widget::row()
+                        .push(text(&theme.name).width(Length::Fill))
+                        .push(button("Apply").on_press(Message::ApplyTheme(index)))
+                        .align_items(Alignment::Center),
+                )
+            })
+            .spacing(space_xxs)
+            .into()
+    }
+
+    /// Sweeps the highlight along every line of the board at once.
+    fn celebrate(&mut self) {
+        let Some(game) = &self.game else {
+            return;
+        };
+        let now = Instant::now();
+        let lines = (0..game.board.height)
+            .map(Line::Row)
+            .chain((0..game.board.width).map(Line::Column));
+        self.line_flashes.extend(lines.map(|line| (line, now)));
+    }
+
+    fn themes_dir(&self) -> Option<PathBuf> {
+        self.data_dir.as_deref().map(themes::directory)
+    }
+
+    fn presets_path(&self) -> Option<PathBuf> {
+        self.data_dir.as_deref().map(|dir| dir.join("presets.toml"))
+    }
+
+    /// Whether a game is in play, neither over nor paused, timed or not.
+    fn playing(&self) -> bool {
+        self.game
+            .as_ref()
+            .is_some_and(|game| matches!(game.winstate, Winstate::InProgress) && !game.paused)
+    }
+
+    /// Counts the time played since [`Self::playing_since`] towards today in the
+    /// statistics. Pauses the game once that uses up the daily budget, and warns shortly
+    /// before it does.
+    fn count_play_time(&mut self) {
+        let Some(since) = self.playing_since.take() else {
+            return;
+        };
+        let now = Instant::now();
+        let today = Local::now().num_days_from_ce();
+        let before = self.statistics.played_on(today);
+        self.count_game(statistics::Outcome::Played(today, now - since));
+        if self.playing() {
+            self.playing_since = Some(now);
+        }
+        if self.daily_budget_minutes == 0 {
+            return;
+        }
+        // Each is told once, as the day's play time passes it in whichever window or
+        // launch that happens, so a player who carries on afterwards isn't nagged.
+        let budget = Duration::from_secs(60 * u64::from(self.daily_budget_minutes));
+        let played = self.statistics.played_on(today);
+        if before < budget && played >= budget {
+            self.playing_since = None;
+            self.notice = Some(match &mut self.game {
+                Some(game) if matches!(game.winstate, Winstate::InProgress) => {
+                    game.pause();
+                    "That's today's play time used up, so your game is paused. It keeps \
+                     until you're back tomorrow!"
+                }
+                _ => "That's today's play time used up, see you tomorrow!",
+            });
+        } else if before + BUDGET_WARNING < budget && played + BUDGET_WARNING >= budget {
+            self.notice = Some("Five minutes of today's play time left");
+        }
+    }
+
+    /// Whether a timed game is on the clock, neither over nor paused.
+    fn clock_ticking(&self) -> bool {
+        self.game.as_ref().is_some_and(|game| {
+            !game.config.relaxed && matches!(game.winstate, Winstate::InProgress) && !game.paused
+        })
+    }
+
+    fn autosave_path(&self) -> Option<PathBuf> {
+        self.data_dir
+            .as_deref()
+            .map(|dir| dir.join("autosave.toml"))
+    }
+
+    /// Keeps a game left in progress in the autosave file, along with the
+    /// [`Self::autosaves_kept`] saves before it, or drops the file when there is nothing
+    /// to keep. Returns whether the game was saved. With autosaving turned off, no file is
+    /// touched.
+    fn autosave(&self) -> bool {
+        let Some(path) = self.autosave_path().filter(|_| self.autosaving) else {
+            return false;
+        };
+        let Some(game) = self
+            .game
+            .as_ref()
+            .filter(|game| matches!(game.winstate, Winstate::InProgress))
+        else {
+            let _result = fs::remove_file(path);
+            return false;
+        };
+        toml::to_string(game)
+            .map_err(|error| error.to_string())
+            .and_then(|contents| {
+                storage::write_rotating(&path, &contents, usize::from(self.autosaves_kept))
+                    .map_err(|error| error.to_string())
+            })
+            .is_ok()
+    }
+
+    /// Picks up the game left in progress when the window was last closed, if there is
+    /// one, resizing the window to fit its board. A file that doesn't hold a sound game in
+    /// play is ignored.
+    fn restore_autosave(&mut self) -> Command<Message> {
+        let Some(mut game) = self
+            .autosave_path()
+            .filter(|_| self.autosaving)
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| toml::from_str::<Game>(&contents).ok())
+            .filter(|game| game.integrity_problems().is_empty())
+        else {
+            return Command::none();
+        };
+        // Starts the clock again where it stood when the game was saved.
+        game.wincheck();
+        if !matches!(game.winstate, Winstate::InProgress) {
+            return Command::none();
+        }
+        // The window opened sized for the default board.
+        self.window_size =
+            window_size_for_board(game.board.width, game.board.height, self.game_scale(&game));
+        self.game = Some(game);
+        self.notice = Some("Restored the game left unfinished last time");
+        window::resize(window::Id::MAIN, self.window_size)
+    }
+
+    /// Writes the saved presets to disk, noting in the menu if that failed.
+    fn store_saved_presets(&mut self) {
+        self.menu.preset_error = match self.presets_path() {
+            Some(path) => setup::store_saved_presets(&path, &self.menu.saved_presets).err(),
+            None => Some(String::from("No data directory to save presets in")),
+        };
+    }
+
+    fn reload_themes(&mut self) {
+        self.themes = self
+            .themes_dir()
+            .map(|dir| themes::load_all(&dir))
+            .unwrap_or_default();
+    }
+
+    /// The current game's playfield with its quick settings above it, framed for the
+    /// stream in streamer mode.
+    fn game_view<'a>(&'a self, game: &'a Game) -> Element<'a, Message> {
+        let mut guides: Vec<Line> = self
+            .hovered_tile
+            .filter(|_| self.crosshair)
+            .map(|id| game.board.lines_through(id))
+            .into_iter()
+            .flatten()
+            .collect();
+        guides.extend(self.tutor.as_ref().and_then(|&(line, _)| line));
+        let view = widget::column().push(self.quick_settings(game));
+        let view = if self.streamer_mode && !game.config.relaxed {
+            view.push(text(format_clock(game.clock.elapsed())).size(STREAMER_CLOCK_SIZE))
+        } else {
+            view
+        };
+        let view = view
+            .push(playfield(
+                game,
+                self.tutor
+                    .as_ref()
+                    .map(|(_, explanation)| explanation.as_str())
+                    .or(self.stuck_nudge.as_deref())
+                    .or(self.notice),
+                &self.line_flashes,
+                &guides,
+                PlayfieldLook {
+                    colors: if self.kid_mode {
+                        &*KID_COLORS
+                    } else {
+                        &self.board_colors
+                    },
+                    mark_style: &self.mark_style,
+                    scale: self.game_scale(game),
+                    private: self.streamer_mode,
+                    opacity: f32::from(self.board_opacity) / 100.0,
+                    clue_font: self.clue_font,
+                },
+            ))
+            .align_items(Alignment::End);
+        let view = if game.config.relaxed {
+            view
+        } else {
+            view.push(self.laps(game))
+        };
+        // A timed win shows how it compares to earlier ones.
+        let view = match self
+            .best_times
+            .iter()
+            .find(|best| best.is_for(&game.config))
+            .filter(|_| matches!(game.winstate, Winstate::Won) && !game.config.relaxed)
+        {
+            Some(best) if !self.streamer_mode => {
+                view.push(best_times_list(best, self.best_time_place))
+            }
+            _ => view,
+        };
+        let view = match &self.win_comparison {
+            Some(comparison) if !self.streamer_mode => {
+                view.push(win_comparison(comparison, &game.config))
+            }
+            _ => view,
+        };
+        if self.streamer_mode {
+            container(view)
+                .style(stream_border(
+                    self.board_colors.color(BoardPart::StreamBorder),
+                    STREAMER_BORDER_WIDTH,
+                ))
+                .padding(STREAMER_BORDER_WIDTH as u16)
+                .into()
+        } else {
+            view.into()
+        }
+    }
+
+    /// Writes the clock and progress of the current game for a streaming overlay to
+    /// read, when asked to.
+    fn write_stream_overlay(&self) {
+        let (Some(game), Some(data_dir)) = (&self.game, &self.data_dir) else {
+            return;
+        };
+        if !(self.streamer_mode && self.stream_overlay) {
+            return;
+        }
+        let contents = format!(
+            "{} \u{b7} {}%\n",
+            format_clock(game.clock.elapsed()),
+            game.progress()
+        );
+        // A stale overlay is no reason to interrupt the game.
+        let _result = storage::write_atomic(&data_dir.join(STREAM_OVERLAY_FILE), &contents);
+    }
+
+    /// The laps taken so far on a timeline, with a field for labelling the next one.
+    fn laps<'a>(&'a self, game: &'a Game) -> Element<'a, Message> {
+        let lap_row = widget::row()
+            .push(
+                text_input("Lap label", &self.lap_label)
+                    .on_input(Message::InputLapLabel)
+                    .on_submit(Message::Lap)
+                    .width(200),
+            )
+            .push(button(text(fl!("lap"))).on_press_maybe(game.can_lap().then_some(Message::Lap)))
+            .align_items(Alignment::Center)
+            .spacing(10);
+        game.laps
+            .iter()
+            .enumerate()
+            .fold(widget::column().push(lap_row), |acc, (index, lap)| {
+                let time = format_clock(lap.at);
+                acc.push(text(if lap.label.is_empty() {
+                    format!("Lap {} \u{b7} {time}", index + 1)
+                } else {
+                    format!("Lap {} \u{b7} {time} \u{b7} {}", index + 1, lap.label)
+                }))
+            })
+            .spacing(5)
+            .into()
+    }
+
+    /// Whether kid mode can't be turned off from within the app, because it was forced on
+    /// from the command line or locked in the settings.
+    fn kid_mode_fixed(&self) -> bool {
+        self.kid_mode_forced || self.kid_mode_locked
+    }
+
+    /// The scale `game` is drawn at, with the tile size it was fitted to the window at if
+    /// it was.
+    fn game_scale(&self, game: &Game) -> BoardScale {
+        game.tile_size
+            .map_or_else(|| self.board_scale(), |tile| self.fitted_scale(tile))
+    }
+
+    /// The usual scale with tiles `tile` pixels high.
+    fn fitted_scale(&self, tile: u16) -> BoardScale {
+        BoardScale {
+            tile,
+            tile_width: tile,
+            ..self.board_scale()
+        }
+        .with_shape(self.cell_shape)
+    }
+
+    /// The bigger of the kid mode and large print scales, when either is on, with the
+    /// chosen cell shape.
+    fn board_scale(&self) -> BoardScale {
+        let scale = if self.large_print {
+            BoardScale::LARGE
+        } else if self.kid_mode {
+            BoardScale::KID
+        } else {
+            BoardScale::NORMAL
+        };
+        scale.with_shape(self.cell_shape)
+    }
+
+    /// A gear button opening toggles that apply to the current game immediately.
+    fn quick_settings(&self, game: &Game) -> Element<Message> {
+        let gear = button(
+            widget::icon::from_name("emblem-system-symbolic")
+                .size(16)
+                .icon(),
+        )
+        .on_press(Message::ToggleQuickSettings);
+        let popover = widget::popover(gear);
+        if !self.quick_settings_open {
+            return popover.into();
+        }
+
+        let toggles = action::ACTIONS
+            .iter()
+            .filter_map(|spec| match spec.kind {
+                action::ActionKind::Toggle(checked) => Some((spec, checked)),
+                action::ActionKind::Button => None,
+            })
+            .fold(
+                assist_checkboxes(game.config.assists, Message::SetGameAssists),
+                |acc, (spec, checked)| {
+                    if (spec.available)(self) {
+                        acc.push(widget::checkbox((spec.label)(), checked(self), |_| {
+                            spec.action.message()
+                        }))
+                    } else {
+                        // Without a press handler the button is drawn disabled.
+                        acc.push(button(text((spec.label)())))
+                    }
+                },
+            )
+            .spacing(10);
+        popover
+            .popup(
+                container(toggles)
+                    .style(theme::Container::Dropdown)
+                    .padding(10),
+            )
+            .into()
+    }
+
+    /// Does the work of [`Application::update`], which checks the result in debug builds.
+    fn handle_message(&mut self, message: Message) -> Command<Message> {
+        #[cfg(feature = "profiling")]
+        profiling::scope!("update");
+
+        if starts_game(&message) && self.tilt.cooling_down(Instant::now()) {
+            self.notice = Some("Still on a break, new games unlock once the 30 seconds are up");
+            return Command::none();
+        }
+        if starts_game(&message)
+            || matches!(
+                message,
+                Message::Reveal(_)
+                    | Message::Mark(_)
+                    | Message::Hint
+                    | Message::Undo
+                    | Message::Redo
+                    | Message::TogglePause
+            )
+        {
+            self.last_move = Instant::now();
+            self.stuck_nudge = None;
+            self.tutor = None;
+        }
+        if starts_game(&message) {
+            self.best_time_place = None;
+            self.win_comparison = None;
+        }
+
+        match message {
+            Message::LaunchUrl(url) => {
+                let _result = open::that_detached(url);
+            }
+
+            Message::ToggleContextPage(context_page) => {
+                if self.context_page == context_page {
+                    // Close the context drawer if the toggled context page is the same.
+                    self.core.window.show_context = !self.core.window.show_context;
+                } else {
+                    // Open the context drawer to display the requested context page.
+                    self.context_page = context_page;
+                    self.core.window.show_context = true;
+                }
+
+                // Set the title of the context drawer.
+                self.set_context_title(context_page.title());
+            }
+            Message::ToggleProgressInTitle => self.progress_in_title = !self.progress_in_title,
+            Message::ToggleCrosshair => self.crosshair = !self.crosshair,
+            Message::ToggleBreakReminders => self.tilt.remind = !self.tilt.remind,
+            Message::ToggleEnforceBreaks => self.tilt.enforce = !self.tilt.enforce,
+            Message::ToggleKidMode => {
+                if !self.kid_mode_fixed() {
+                    self.kid_mode = !self.kid_mode;
+                }
+            }
+            Message::LockKidMode => {
+                self.kid_mode = true;
+                self.kid_mode_locked = true;
+            }
+            Message::ToggleLargePrint => self.large_print = !self.large_print,
+            Message::ToggleStuckNudges => {
+                self.stuck_nudges = !self.stuck_nudges;
+                self.stuck_nudge = None;
+            }
+            Message::ToggleSaveNotification => self.save_notification = !self.save_notification,
+            Message::ToggleStreamerMode => self.streamer_mode = !self.streamer_mode,
+            Message::ToggleStreamOverlay => {
+                self.stream_overlay = !self.stream_overlay;
+                self.write_stream_overlay();
+            }
+            Message::ToggleAutosave(autosaving) => self.autosaving = autosaving,
+            Message::SetAutosaveMinutes(minutes) => {
+                self.autosave_minutes = minutes.min(MAX_AUTOSAVE_MINUTES);
+            }
+            Message::SetAutosavesKept(kept) => {
+                self.autosaves_kept = kept.clamp(1, MAX_AUTOSAVES_KEPT);
+            }
+            Message::AutosaveTick => {
+                self.autosave();
+            }
+            Message::TogglePause => {
+                if let Some(game) = &mut self.game {
+                    if game.paused {
+                        game.resume();
+                    } else {
+                        game.pause();
+                    }
+                }
+            }
+            Message::SetIdlePauseMinutes(minutes) => {
+                self.idle_pause_minutes = minutes.min(MAX_IDLE_PAUSE_MINUTES);
+            }
+            Message::ToggleIdlePauseNotification(notify) => self.idle_pause_notification = notify,
+            Message::IdleTick(now) => {
+                let idle = Duration::from_secs(60 * u64::from(self.idle_pause_minutes));
+                if let Some(game) = self.game.as_mut().filter(|game| {
+                    !game.paused && !game.config.relaxed && now - self.last_move >= idle
+                }) {
+                    // The time spent away is taken back off the clock too.
+                    game.pause_since(self.last_move);
+                    self.notice = Some("Paused after a while without a move");
+                    if self.idle_pause_notification {
+                        let _result = notify_rust::Notification::new()
+                            .appname(&fl!("app-title"))
+                            .summary("Your game was paused")
+                            .body(&format!(
+                                "No moves for {} minutes, so the clock was stopped",
+                                self.idle_pause_minutes
+                            ))
+                            .show();
+                    }
+                }
+            }
+            Message::SetDailyBudgetMinutes(minutes) => {
+                self.daily_budget_minutes = minutes.min(MAX_DAILY_BUDGET_MINUTES);
+            }
+            Message::PlayTick => self.count_play_time(),
+            Message::WindowClosing => {
+                self.count_play_time();
+                let saved = self.autosave();
+                // The window is gone by the time anyone could act on a failure.
+                if let Some(game) = self
+                    .game
+                    .as_ref()
+                    .filter(|_| saved && self.save_notification)
+                {
+                    let _result = notify_rust::Notification::new()
+                        .appname(&fl!("app-title"))
+                        .summary(&format!("Game saved — {}% complete", game.progress()))
+                        .show();
+                }
+            }
+            Message::SettingsChanged(settings) => {
+                // The board size another window typed would clobber the one being typed
+                // here, and only matters on the next launch anyway.
+                self.apply_settings(&settings);
+                self.settings = Settings {
+                    width: self.settings.width,
+                    height: self.settings.height,
+                    filled_count: self.settings.filled_count,
+                    ..settings
+                };
+            }
+            Message::StuckTick(now) => {
+                if let Some(game) = self
+                    .game
+                    .as_ref()
+                    .filter(|_| now - self.last_move >= STUCK_AFTER)
+                {
+                    self.stuck_nudge = Some(match game.board.forced_line() {
+                        Some(Line::Row(row)) => {
+                            format!("Stuck? There's a forced move in row {}", row + 1)
+                        }
+                        Some(Line::Column(column)) => {
+                            format!("Stuck? There's a forced move in column {}", column + 1)
+                        }
+                        None => String::from(
+                            "Stuck? No single row or column settles another tile right now",
+                        ),
+                    });
+                }
+            }
+            Message::StartKidGame => self.start_game(Game::with_config(setup::kid_config())),
+            Message::HoverTile(id) => self.hovered_tile = Some(id),
+            // The next tile may be entered before this one is left.
+            Message::LeaveTile(id) => {
+                if self.hovered_tile == Some(id) {
+                    self.hovered_tile = None;
+                }
+            }
+            Message::Key(modifiers, key) => {
+                if let Some(action) = action::for_key(self, modifiers, &key) {
+                    return self.update(action.message());
+                }
+                if let (None, true, Key::Character(character)) =
+                    (&self.game, modifiers.alt(), key.as_ref())
+                {
+                    // Alt+1, Alt+2, ... pick the matching preset.
+                    if let Some(index) = character
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|number| number.checked_sub(1))
+                        .filter(|&index| index < PRESETS.len())
+                    {
+                        self.menu.apply_preset(&PRESETS[index]);
+                    }
+                }
+            }
+            Message::ApplyPreset(index) => self.menu.apply_preset(&PRESETS[index]),
+            Message::InputPresetName(name) => self.menu.preset_name = name,
+            Message::SavePreset => match self.menu.save_preset() {
+                Ok(()) => self.store_saved_presets(),
+                Err(error) => self.menu.error = Some(error),
+            },
+            Message::ApplySavedPreset(index) => self.menu.apply_saved_preset(index),
+            Message::DeleteSavedPreset(index) => {
+                if index < self.menu.saved_presets.len() {
+                    self.menu.saved_presets.remove(index);
+                    self.store_saved_presets();
+                }
+            }
+            Message::Reveal(id) => {
+                self.notice = None;
+                if let Some(game) = &mut self.game {
+                    let events = game.reveal(id);
+                    return self.handle_game_events(&events);
+                }
+            }
+            Message::Undo => {
+                self.notice = None;
+                if let Some(game) = &mut self.game {
+                    game.undo();
+                }
+            }
+            Message::Redo => {
+                self.notice = None;
+                if let Some(game) = &mut self.game {
+                    game.redo();
+                }
+            }
+            Message::InputLapLabel(label) => self.lap_label = label,
+            Message::Lap => {
+                if let Some(game) = &mut self.game {
+                    game.lap(std::mem::take(&mut self.lap_label).trim().to_string());
+                }
+            }
+            Message::Hint => {
+                self.notice = None;
+                if let Some(game) = &mut self.game {
+                    let events = game.hint();
+                    return self.handle_game_events(&events);
+                }
+            }
+            Message::Explain => {
+                // Asking again before the next move shows the same step, which is only
+                // paid for once.
+                if let Some(game) = self
+                    .game
+                    .as_mut()
+                    .filter(|game| matches!(game.winstate, Winstate::InProgress) && !game.paused)
+                    .filter(|_| self.tutor.is_none())
+                {
+                    let (line, explanation) = explain(&game.board);
+                    // Explaining a step gives it away as much as a hint does.
+                    if line.is_some() {
+                        game.charge_hint();
+                    }
+                    self.tutor = Some((line, explanation));
+                }
+            }
+            Message::Mark(id) => {
+                self.notice = None;
+                if let Some(game) = &mut self.game {
+                    let events = game.toggle_mark(id);
+                    return self.handle_game_events(&events);
+                }
+            }
+            Message::ToggleQuickSettings => self.quick_settings_open = !self.quick_settings_open,
+            Message::SetGameAssists(assists) => {
+                if let Some(game) = &mut self.game {
+                    game.set_assists(assists);
+                }
+            }
+            Message::InputBookmarkName(name) => self.bookmark_name = name,
+            Message::InputNote(note) => {
+                if let Some(game) = &mut self.game {
+                    game.note = note;
+                }
+            }
+            Message::AddBookmark => {
+                if let Some(game) = &mut self.game {
+                    let name = match self.bookmark_name.trim() {
+                        "" => format!("Bookmark {}", game.bookmarks.len() + 1),
+                        name => name.to_string(),
+                    };
+                    game.add_bookmark(name);
+                    self.bookmark_name.clear();
+                }
+            }
+            Message::RestoreBookmark(index) => {
+                if let Some(game) = &mut self.game {
+                    game.restore_bookmark(index);
+                }
+            }
+            Message::GotoMenu => {
+                self.abandon_game();
+                self.game = None;
+                self.generating = None;
+                // The board previewed last is the one just played.
+                self.menu.shuffle_preview();
+                self.notice = None;
+                self.quick_settings_open = false;
+                return Command::batch([
+                    widget::text_input::focus(WIDTH_INPUT_ID.clone()),
+                    self.refresh_title_progress(),
+                ]);
+            }
+            Message::InputWidth(input) => {
+                self.menu.width_input = input;
+                self.menu.refresh_preview();
+            }
+            Message::InputHeight(input) => {
+                self.menu.height_input = input;
+                self.menu.refresh_preview();
+            }
+            Message::InputFilledCount(input) => {
+                self.menu.filled_count_input = input;
+                self.menu.refresh_preview();
+            }
+            Message::InputSeed(input) => {
+                self.menu.seed_input = input;
+                self.menu.refresh_preview();
+            }
+            Message::CopyShareCode => {
+                if let Some(code) = self.game.as_ref().and_then(Game::share_code) {
+                    return clipboard::write(code);
+                }
+            }
+            Message::ShufflePreview => self.menu.shuffle_preview(),
+            Message::ToggleRelaxed(relaxed) => self.menu.relaxed = relaxed,
+            Message::ToggleNoGuessing(no_guessing) => self.menu.no_guessing = no_guessing,
+            Message::SetHints(hints) => self.menu.hints = hints.min(MAX_HINTS),
+            Message::SetAssists(assists) => self.menu.assists = assists,
+            Message::SetForgiveness(seconds) => {
+                self.menu.forgiveness_seconds = seconds.min(MAX_FORGIVENESS_SECONDS);
+            }
+            Message::StartPressed => match self.menu.config() {
+                Ok(config) => {
+                    self.menu.error = None;
+                    return self.generate_game(config, self.menu.start_seed(&config), None);
+                }
+                Err(error) => self.menu.error = Some(error),
+            },
+
+            Message::StartWeekly => {
+                let config = challenge::Week::current().config();
+                return self.generate_game(config, config.pick_seed(), None);
+            }
+
+            Message::SurpriseMe => {
+                self.menu.error = None;
+                let config = self.menu.surprise_config();
+                return self.generate_game(config, config.pick_seed(), None);
+            }
+
+            Message::FitScreen => {
+                self.menu.error = None;
+                let tile = self.menu.fit_tile_size;
+                let (width, height) = board_fitting(self.window_size, self.fitted_scale(tile));
+                let config = self.menu.random_density_config(width, height);
+                // Drawn at the usual size, the board would no longer match the window.
+                return self.generate_game(config, config.pick_seed(), Some(tile));
+            }
+            Message::BoardGenerated(generating, board, solvability) => {
+                // Boards asked for before the last start, or before going back to the
+                // menu, are no longer wanted.
+                if self.generating == Some(generating) {
+                    let mut game = Game::with_generated(
+                        generating.config,
+                        generating.seed,
+                        board,
+                        solvability,
+                    );
+                    game.tile_size = generating.tile_size;
+                    self.start_game(game);
+                }
+            }
+            Message::SetFitTileSize(size) => {
+                self.menu.fit_tile_size = size.clamp(MIN_FIT_TILE_SIZE, MAX_FIT_TILE_SIZE);
+            }
+            Message::WindowResized(size) => self.window_size = size,
+
+            Message::StartSeasonal(index) => {
+                self.start_with_board(Board::from_rows(seasonal::PUZZLES[index].rows));
+            }
+            Message::InputImportPath(path) => self.menu.import_path = path,
+            Message::InputBoardColor(part, input) => self.board_colors.set_input(part, input),
+            Message::SetMarkGlyph(glyph) => self.mark_style.glyph = glyph,
+            Message::InputCustomMark(custom) => {
+                self.mark_style.custom = custom;
+                self.mark_style.glyph = MarkGlyph::Custom;
+            }
+            Message::SetMarkSize(size) => {
+                self.mark_style.size = size.clamp(mark::MIN_SIZE, mark::MAX_SIZE);
+            }
+            Message::SetBoardOpacity(opacity) => {
+                self.board_opacity = opacity.clamp(MIN_BOARD_OPACITY, 100);
+            }
+            Message::SetCellShape(shape) => self.cell_shape = shape,
+            Message::SetClueFont(font) => self.clue_font = font,
+            Message::InputThemeName(name) => self.theme_name = name,
+            Message::ExportTheme => {
+                let theme = themes::BoardTheme {
+                    name: self.theme_name.trim().to_string(),
+                    colors: self.board_colors.clone(),
+                };
+                self.theme_status = Some(match self.themes_dir() {
+                    _ if theme.name.is_empty() => String::from("Name the theme first"),
+                    Some(dir) => match themes::export(&dir, &theme) {
+                        Ok(path) => format!("Exported to {}", path.display()),
+                        Err(error) => error,
+                    },
+                    None => String::from("No data directory to save themes in"),
+                });
+                self.reload_themes();
+            }
+            Message::InputThemeImportPath(path) => self.theme_import_path = path,
+            Message::ImportTheme => {
+                self.theme_status = Some(match self.themes_dir() {
+                    Some(dir) => {
+                        match themes::import(&dir, Path::new(self.theme_import_path.trim())) {
+                            Ok(theme) => {
+                                self.board_colors = theme.colors;
+                                format!("Imported {}", theme.name)
+                            }
+                            Err(error) => error,
+                        }
+                    }
+                    None => String::from("No data directory to save themes in"),
+                });
+                self.reload_themes();
+            }
+            Message::ApplyTheme(index) => {
+                if let Some(theme) = self.themes.get(index) {
+                    self.board_colors = theme.colors.clone();
+                    self.theme_name = theme.name.clone();
+                }
+            }
+            Message::FlashTick(now) => self
+                .line_flashes
+                .retain(|&(_, started)| now - started < LINE_FLASH_DURATION),
+            Message::ClockTick => {
+                let now = (Instant::now(), SystemTime::now());
+                if let Some((ticked, ticked_at)) = self.last_tick.replace(now) {
+                    // The monotonic clock may or may not count time spent asleep, the
+                    // wall clock always does, so a gap there is how waking up shows.
+                    let away = now.1.duration_since(ticked_at).unwrap_or_default();
+                    if let Some(game) = self.game.as_mut().filter(|_| away >= SUSPEND_GAP) {
+                        game.pause_since(ticked);
+                        self.notice = Some("Paused while the computer was asleep");
+                    }
+                }
+                self.write_stream_overlay();
+            }
+            Message::ImportFile => match std::fs::read_to_string(self.menu.import_path.trim()) {
+                Ok(input) => self.import_grid(&input),
+                Err(error) => self.menu.import_error = Some(error.to_string()),
+            },
+            Message::PasteGrid => {
+                return clipboard::read(|contents| {
+                    cosmic::app::Message::App(Message::ImportGrid(contents))
+                });
+            }
+            Message::ImportGrid(contents) => match contents {
+                Some(input) => self.import_grid(&input),
+                None => self.menu.import_error = Some(String::from("The clipboard is empty")),
+            },
+
+            Message::Reset => {
+                if let Some(game) = self.game.as_ref().filter(|game| !game.fixed) {
+                    let (config, tile_size) = (game.config, game.tile_size);
+                    return self.generate_game(config, config.pick_seed(), tile_size);
+                }
+                if self.game.is_some() {
+                    self.abandon_game();
+                    self.count_game(statistics::Outcome::Started);
+                }
+                if let Some(game) = &mut self.game {
+                    game.reset();
+                }
+                self.notice_solvability();
+            }
+            Message::Restart => {
+                self.notice = None;
+                if self.game.is_some() {
+                    self.abandon_game();
+                    self.count_game(statistics::Outcome::Started);
+                }
+                if let Some(game) = &mut self.game {
+                    game.restart();
+                }
+            }
+        }
+        self.refresh_title_progress()
+    }
+
+    /// Panics with everything that's wrong once a message left the game in an impossible
+    /// state, so bugs in move handling show up right where they happen.
+    #[cfg(debug_assertions)]
+    fn check_integrity(&self) {
+        let Some(game) = &self.game else {
+            return;
+        };
+        let problems = game.integrity_problems();
+        assert!(
+            problems.is_empty(),
+            "the game is in an inconsistent state:\n- {}",
+            problems.join("\n- ")
+        );
+    }
+
+    /// Updates the header and window titles.
+    pub fn update_titles(&mut self) -> Command<Message> {
+        let window_title = match self.title_progress {
+            Some(progress) => format!("{} \u{2014} {progress}%", fl!("app-title")),
+            None => fl!("app-title"),
+        };
+        let header_title = String::new();
+
+        self.set_header_title(header_title);
+        self.set_window_title(window_title)
+    }
+
+    /// Lets every subsystem react to what just happened in the game.
+    fn handle_game_events(&mut self, events: &[GameEvent]) -> Command<Message> {
+        let commands: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                GameEvent::TileRevealed(_) => Some(self.refresh_title_progress()),
+                GameEvent::GameWon => {
+                    self.tilt.record_win();
+                    if self.settle_game() {
+                        self.record_best_time();
+                        let timed = self
+                            .game
+                            .as_ref()
+                            .filter(|game| !game.config.relaxed)
+                            .map(|game| (game.config, game.clock.elapsed()));
+                        // Compared before it is counted, so the win isn't measured
+                        // against itself.
+                        self.win_comparison =
+                            timed.and_then(|(config, time)| self.statistics.compare(&config, time));
+                        self.count_game(statistics::Outcome::Won(timed));
+                    }
+                    self.write_stream_overlay();
+                    if self.kid_mode {
+                        self.celebrate();
+                    }
+                    Some(self.refresh_title_progress())
+                }
+                GameEvent::GameLost => {
+                    self.write_stream_overlay();
+                    if self.settle_game() {
+                        self.count_game(statistics::Outcome::Lost);
+                    }
+                    if self.tilt.record_loss(Instant::now()) {
+                        self.notice = Some(if self.tilt.enforce {
+                            "Three quick losses. Take a breath, new games unlock in 30 seconds"
+                        } else {
+                            "Three quick losses. Maybe take a breath before the next one?"
+                        });
+                    }
+                    None
+                }
+                GameEvent::RevealBlocked(_) => {
+                    self.notice = Some("That line is already complete");
+                    None
+                }
+                GameEvent::MistakeForgiven(_) => {
+                    self.notice = Some("Oops! That tile is empty, so it was marked instead");
+                    None
+                }
+                GameEvent::LineCompleted(line) => {
+                    self.line_flashes.push((*line, Instant::now()));
+                    None
+                }
+                GameEvent::HintUnavailable => {
+                    self.notice = Some("Nothing follows from the board as it is without guessing");
+                    None
+                }
+                GameEvent::MistakeMade(_)
+                | GameEvent::TileMarked { .. }
+                | GameEvent::HintUsed(_) => None,
+            })
+            .collect();
+        Command::batch(commands)
+    }
+
+    /// Updates the titles when the progress percentage they should show has changed.
+    fn refresh_title_progress(&mut self) -> Command<Message> {
+        let progress = match &self.game {
+            Some(game) if self.progress_in_title => Some(game.progress()),
+            _ => None,
+        };
+        if progress == self.title_progress {
+            Command::none()
+        } else {
+            self.title_progress = progress;
+            self.update_titles()
+        }
+    }
+}
+
+/// The tutor's account of the next deduction on `board` and the line to highlight for it.
+fn explain(board: &Board) -> (Option<Line>, String) {
+    let Some(deduction) = board.next_deduction() else {
+        return (
+            None,
+            String::from("Nothing follows from the board as it is without guessing"),
+        );
+    };
+    let (row, column) = (
+        deduction.id / board.width + 1,
+        deduction.id % board.width + 1,
+    );
+    let outcome = if deduction.filled {
+        "must be filled"
+    } else {
+        "stays empty"
+    };
+    match deduction.reason {
+        Reason::Line(line, step) => {
+            let (name, number, across, position) = match line {
+                Line::Row(_) => ("Row", row, "column", column),
+                Line::Column(_) => ("Column", column, "row", row),
+            };
+            let explanation = match step {
+                LineStep::Overlap { run, first, last } if first == last => format!(
+                    "however the {run}-run is placed, it covers {across} {}",
+                    first + 1
+                ),
+                LineStep::Overlap { run, first, last } => format!(
+                    "however the {run}-run is placed, it covers {across}s {}–{}",
+                    first + 1,
+                    last + 1
+                ),
+                LineStep::Unreachable => {
+                    format!("no run can reach {across} {position}, so it {outcome}")
+                }
+                LineStep::Exhaustive => {
+                    format!(
+                        "every way of fitting its clues agrees that {across} {position} {outcome}"
+                    )
+                }
+            };
+            (Some(line), format!("{name} {number}: {explanation}"))
+        }
+        Reason::Contradiction => {
+            let other = if deduction.filled {
+                "leaving it empty"
+            } else {
+                "filling it"
+            };
+            (
+                Some(Line::Row(row - 1)),
+                format!(
+                    "Row {row}, column {column}: {other} contradicts the clues further on, so it {outcome}"
+                ),
+            )
+        }
+    }
+}
+
+/// Whether `message` replaces the current game with a new one, which an enforced break
+/// holds back.
+fn starts_game(message: &Message) -> bool {
+    matches!(
+        message,
+        Message::StartPressed
+            | Message::StartWeekly
+            | Message::SurpriseMe
+            | Message::FitScreen
+            | Message::StartSeasonal(_)
+            | Message::ImportFile
+            | Message::ImportGrid(_)
+            | Message::Reset
+            | Message::Restart
+    )
+}
+
+/// The best times of one board size, with the one at `highlight` marked as new.
+fn best_times_list(best: &records::BestTimes, highlight: Option<usize>) -> Element<'_, Message> {
+    best.times
+        .iter()
+        .enumerate()
+        .fold(
+            widget::column().push(text(format!(
+                "{}×{}, {} filled",
+                best.width, best.height, best.filled_count
+            ))),
+            |acc, (place, best)| {
+                let time = format_clock(Duration::from_millis(best.millis));
+                let time = match best.hints {
+                    0 => time,
+                    1 => format!("{time}, 1 hint"),
+                    hints => format!("{time}, {hints} hints"),
+                };
+                acc.push(text(if highlight == Some(place) {
+                    format!("{}. {time} (new)", place + 1)
+                } else {
+                    format!("{}. {time}", place + 1)
+                }))
+            },
+        )
+        .into()
+}
+
+/// A timed win next to the average and best of earlier wins of its board size, with
+/// bars as long as each time.
+fn win_comparison<'a>(
+    comparison: &statistics::Comparison,
+    config: &GameConfig,
+) -> Element<'a, Message> {
+    let size = format!("{}×{}", config.width, config.height);
+    let summary = match comparison.percent_faster() {
+        0 => format!("Right on your average {size}"),
+        percent if percent > 0 => format!("{percent}% faster than your average {size}"),
+        percent => format!("{}% slower than your average {size}", -percent),
+    };
+    let longest = comparison
+        .time
+        .max(comparison.average)
+        .max(comparison.best)
+        .as_secs_f32()
+        .max(f32::EPSILON);
+    [
+        ("This solve", comparison.time),
+        ("Average", comparison.average),
+        ("Best", comparison.best),
+    ]
+    .into_iter()
+    .fold(widget::column().push(text(summary)), |acc, (name, time)| {
+        acc.push(
+            widget::row()
+                .push(text(name).width(COMPARISON_LABEL_WIDTH))
+                .push(
+                    container(text(format_clock(time)).size(12))
+                        .style(progress_fill(
+                            theme::Container::Card,
+                            time.as_secs_f32() / longest,
+                            false,
+                        ))
+                        .width(COMPARISON_BAR_WIDTH)
+                        .padding([0, 4]),
+                )
+                .align_items(Alignment::Center),
+        )
+    })
+    .spacing(4)
+    .into()
+}
+
+/// Formats a duration as `M:SS`, or as `H:MM:SS` from an hour on.
+/// A stretch of play in hours and whole minutes.
+fn format_play_time(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{minutes} min"),
+        (hours, 0) => format!("{hours} h"),
+        (hours, minutes) => format!("{hours} h {minutes} min"),
+    }
+}
+
+fn format_clock(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// How the playfield is drawn, apart from the game on it.
+struct PlayfieldLook<'a> {
+    colors: &'a BoardColors,
+    mark_style: &'a MarkStyle,
+    scale: BoardScale,
+    /// Leaves out the attempt count, the combos and the share code, which gives away the
+    /// seed.
+    private: bool,
+    /// How opaque the clue panels, grid and background are, from 0 to 1.
+    opacity: f32,
+    clue_font: ClueFont,
+}
+
+#[cfg_attr(feature = "profiling", profiling::function)]
+fn playfield<'a>(
+    game: &'a Game,
+    notice: Option<&'a str>,
+    line_flashes: &[(Line, Instant)],
+    guides: &[Line],
+    look: PlayfieldLook,
+) -> widget::Container<'a, Message, cosmic::Theme> {
+    let PlayfieldLook {
+        colors,
+        mark_style,
+        scale,
+        private,
+        opacity,
+        clue_font,
+    } = look;
+    let chrome = |part, fallback| translucent(colors.style(part, fallback), opacity);
+    let on_row_guide = |row: usize| guides.contains(&Line::Row(row));
+    let on_column_guide = |column: usize| guides.contains(&Line::Column(column));
+    // The highlight runs from the start of the line to its end, a few tiles long.
+    let is_flashing = |id: usize| {
+        line_flashes.iter().any(|&(line, started)| {
+            let (position, length) = match line {
+                Line::Row(_) => (id % game.board.width, game.board.width),
+                Line::Column(_) => (id / game.board.width, game.board.height),
+            };
+            let head = started.elapsed().as_secs_f32() / LINE_FLASH_DURATION.as_secs_f32()
+                * (length + 2) as f32;
+            game.board.lines_through(id).contains(&line)
+                && (position as f32) <= head
+                && head - (position as f32) < 3.0
+        })
+    };
+    let tile_style = |id: usize, style: theme::Container| {
+        if is_flashing(id) {
+            theme::Container::custom(orange1theme)
+        } else if on_row_guide(id / game.board.width) || on_column_guide(id % game.board.width) {
+            guided(style)
+        } else {
+            style
+        }
+    };
+    let disabled_tilebutton = |id: usize| match game.board.board_vec[id] {
+        Tile {
+            hidden: true,
+            marked: true,
+            ..
+        } => mouse_area(
+            container(centralize_tile_content(
+                text(mark_style.text())
+                    .size(mark_style.font_size(scale.tile.min(scale.tile_width))),
+            ))
+            .style(tile_style(id, theme::Container::Secondary))
+            .center_x()
+            .center_y()
+            .height(scale.tile)
+            .width(scale.tile_width),
+        ),
+        Tile {
+            hidden: true,
+            marked: false,
+            ..
+        } => mouse_area(
+            container("")
+                .style(tile_style(id, theme::Container::Secondary))
+                .center_x()
+                .center_y()
+                .height(scale.tile)
+                .width(scale.tile_width),
+        ),
+        Tile {
+            hidden: false,
+            empty: true,
+            ..
+        } => mouse_area(
+            container("")
+                .style(tile_style(id, theme::Container::custom(gray1theme)))
+                .center_x()
+                .center_y()
+                .height(scale.tile)
+                .width(scale.tile_width),
+        ),
+        Tile {
+            hidden: false,
+            empty: false,
+            ..
+        } => mouse_area(
+            container("")
+                .style(tile_style(id, theme::Container::custom(blacktheme)))
+                .center_x()
+                .center_y()
+                .height(scale.tile)
+                .width(scale.tile_width),
+        ),
+    };
+    let tilebutton = |id: usize| {
+        let tile = disabled_tilebutton(id)
+            .on_enter(Message::HoverTile(id))
+            .on_exit(Message::LeaveTile(id));
+        match game.winstate {
+            Winstate::Won => tile,
+            Winstate::Lost => tile,
+            Winstate::InProgress => tile
+                .on_press(Message::Reveal(id))
+                .on_right_press(Message::Mark(id)),
+        }
+    };
+    // A paused game hides its tiles, so it can't be thought over off the clock.
+    let playboard: Element<'a, Message> = if game.paused {
+        widget::column()
+            .push(text("Paused").size(scale.text))
+            .push(button(text("Resume").size(scale.text)).on_press(Message::TogglePause))
+            .align_items(Alignment::Center)
+            .spacing(10)
+            .into()
+    } else {
+        (0..game.board.height)
+            .fold(Grid::new(), |acc, row| {
+                let new_row = (0..game.board.width).fold(Row::new(), |acc2, column| {
+                    acc2.push(tilebutton(pair_to_index(row, column, game.board.width)))
+                });
+                acc.push(new_row.spacing(2).align_items(Alignment::Center))
+                    .insert_row()
+            })
+            .row_spacing(2)
+            .row_alignment(Alignment::Center)
+            .into()
+    };
+    let menu_button = button(text("Menu").size(scale.text))
+        .on_press(Message::GotoMenu)
+        .style(theme::Button::Suggested);
+    let reset_button = button(text("Reset").size(scale.text))
+        .on_press(Message::Reset)
+        .style(theme::Button::Destructive);
+    let undo_button = button(text("Undo").size(scale.text))
+        .on_press_maybe(game.can_undo().then_some(Message::Undo));
+    let redo_button = button(text("Redo").size(scale.text))
+        .on_press_maybe(game.can_redo().then_some(Message::Redo));
+    let share_button = button(text("Copy share code").size(scale.text)).on_press_maybe(
+        game.share_code()
+            .filter(|_| !private)
+            .map(|_| Message::CopyShareCode),
+    );
+    let hint_label = match game.hints_left() {
+        0 if game.config.relaxed => String::from("Hint"),
+        0 => format!("Hint (+{} s)", game.hint_penalty().as_secs()),
+        left => format!("Hint ({left} left)"),
+    };
+    let playing = matches!(game.winstate, Winstate::InProgress) && !game.paused;
+    let pause_button = button(text(if game.paused { "Resume" } else { "Pause" }).size(scale.text))
+        .on_press_maybe(
+            matches!(game.winstate, Winstate::InProgress).then_some(Message::TogglePause),
+        );
+    let hint_button =
+        button(text(hint_label).size(scale.text)).on_press_maybe(playing.then_some(Message::Hint));
+    let explain_label = match game.hints_left() {
+        0 if game.config.relaxed => String::from("Explain next step"),
+        0 => format!("Explain next step (+{} s)", game.hint_penalty().as_secs()),
+        _ => String::from("Explain next step (uses a hint)"),
+    };
+    let explain_button = button(text(explain_label).size(scale.text))
+        .on_press_maybe(playing.then_some(Message::Explain));
+    let bookmarks_button = button(text("Bookmarks").size(scale.text))
+        .on_press(Message::ToggleContextPage(ContextPage::Bookmarks));
+    let winstate_text = match game.winstate {
+        Winstate::Won => "You won!",
+        Winstate::Lost => "You lost!",
+        Winstate::InProgress => "Game in progress...",
+    };
+    let mut winstate_text = if game.mistake_forgiven {
+        format!("{winstate_text} (one mistake forgiven)")
+    } else {
+        winstate_text.to_string()
+    };
+    if !game.config.relaxed {
+        let clock = format_clock(game.clock.elapsed());
+        winstate_text = match game.winstate {
+            Winstate::InProgress => format!("{winstate_text} {clock}"),
+            Winstate::Won | Winstate::Lost => format!("{winstate_text} Time: {clock}"),
+        };
+        match game.hints_used {
+            0 => {}
+            1 => winstate_text.push_str(" with 1 hint"),
+            hints => winstate_text.push_str(&format!(" with {hints} hints")),
+        }
+    }
+    let vertical_count_column = |vec: &Clues| {
+        vec.iter()
+            .fold(Column::new(), |acc: Column<'_, Message>, count| {
+                acc.push(
+                    container(centralize_tile_content(clue_font.text(*count, scale.text)))
+                        .height(scale.clue)
+                        .center_x()
+                        .center_y(),
+                )
+            })
+    };
+    let vertical_counts =
+        (&game)
+            .board
+            .vertical_count
+            .iter()
+            .enumerate()
+            .fold(Row::new(), |acc, (index, column)| {
+                let style = progress_fill(
+                    chrome(BoardPart::Clues, theme::Container::Primary),
+                    game.board.line_progress(Line::Column(index)),
+                    true,
+                );
+                acc.push(
+                    container(vertical_count_column(column).align_items(Alignment::Center))
+                        .style(if on_column_guide(index) {
+                            guided(style)
+                        } else {
+                            style
+                        })
+                        .width(scale.tile_width)
+                        .center_x()
+                        .center_y(),
+                )
+            });
+    let horizontal_count_row = |vec: &Clues| {
+        vec.iter().fold(Row::new(), |acc: Row<'_, Message>, count| {
+            acc.push(
+                container(centralize_tile_content(clue_font.text(*count, scale.text)))
+                    .width(scale.clue)
+                    .center_x()
+                    .center_y(),
+            )
+        })
+    };
+    let horizontal_counts = (&game).board.horizontal_count.iter().enumerate().fold(
+        Column::new(),
+        |acc, (index, row)| {
+            let style = progress_fill(
+                chrome(BoardPart::Clues, theme::Container::Primary),
+                game.board.line_progress(Line::Row(index)),
+                false,
+            );
+            acc.push(
+                container(horizontal_count_row(row).align_items(Alignment::Center))
+                    .style(if on_row_guide(index) {
+                        guided(style)
+                    } else {
+                        style
+                    })
+                    .height(scale.tile)
+                    .center_x()
+                    .center_y(),
+            )
+        },
+    );
+
+    container(
+        widget::column()
+            .push(
+                container(
+                    container(vertical_counts.spacing(2).align_items(Alignment::End))
+                        .style(chrome(BoardPart::Clues, theme::Container::Primary))
+                        .height(scale.clue_panel(game.board.height))
+                        .center_x()
+                        .align_y(Vertical::Bottom),
+                )
+                .style(chrome(BoardPart::Clues, theme::Container::Primary))
+                .align_x(Horizontal::Right)
+                .width(scale.board_width(game.board.width))
+                .center_x()
+                .center_y()
+                .padding(0),
+            )
+            .push(
+                widget::column()
+                    .push(
+                        widget::row()
+                            .push(
+                                container(
+                                    container(
+                                        horizontal_counts.spacing(2).align_items(Alignment::End),
                                     )
-                                    .width((((game.board.width + 1) / 2) * 20) as u16)
-                                    .style(theme::Container::Primary)
+                                    .width(scale.clue_panel(game.board.width))
+                                    .style(chrome(BoardPart::Clues, theme::Container::Primary))
                                     .align_x(Horizontal::Right)
                                     .center_y(),
                                 )
-                                .style(theme::Container::Primary)
-                                .height((52 * game.board.height + 2) as f32)
+                                .style(chrome(BoardPart::Clues, theme::Container::Primary))
+                                .height(scale.board_height(game.board.height))
                                 .center_x()
                                 .center_y()
                                 .padding(0),
                             )
                             .push(
-                                container(
-                                    playboard.row_spacing(2).row_alignment(Alignment::Center),
-                                )
-                                .style(theme::Container::Primary)
-                                .width((52 * game.board.width + 2) as f32)
-                                .height((52 * game.board.height + 2) as f32)
-                                .center_x()
-                                .center_y()
-                                .padding(0),
+                                container(playboard)
+                                    .style(chrome(BoardPart::Grid, theme::Container::Primary))
+                                    .width(scale.board_width(game.board.width))
+                                    .height(scale.board_height(game.board.height))
+                                    .center_x()
+                                    .center_y()
+                                    .padding(0),
                             )
                             .align_items(Alignment::Center),
                     )
@@ -445,15 +2740,50 @@ fn playfield(game: &Game) -> widget::Container<'_, Message, cosmic::Theme> {
                         widget::row()
                             .push(menu_button)
                             .push(reset_button)
+                            .push(undo_button)
+                            .push(redo_button)
+                            .push(pause_button)
+                            .push(hint_button)
+                            .push(explain_button)
+                            .push(bookmarks_button)
+                            .push(share_button)
                             .padding(20)
                             .spacing(20),
                     )
-                    .push(container(text(winstate_text)))
+                    .push(container(text(winstate_text).size(scale.text)))
+                    .push(
+                        text(if private {
+                            String::new()
+                        } else {
+                            format!("Attempt {}", game.attempts)
+                        })
+                        .size(scale.text),
+                    )
+                    .push(
+                        text(format!(
+                            "Assists: {}",
+                            game.config
+                                .assists
+                                .preset()
+                                .map_or("Custom", |preset| preset.name)
+                        ))
+                        .size(scale.text),
+                    )
+                    .push(
+                        text(match game.combo {
+                            _ if private => String::new(),
+                            0 | 1 => format!("Best combo: {}", game.best_combo),
+                            combo => format!("Combo ×{combo} (best {})", game.best_combo),
+                        })
+                        .size(scale.text),
+                    )
+                    .push(text(notice.unwrap_or_default()).size(scale.text))
                     .align_items(Alignment::Center),
             )
             .align_items(Alignment::End),
     )
     .padding(20)
+    .style(chrome(BoardPart::Background, theme::Container::Transparent))
     .align_x(Horizontal::Right)
     .align_y(Vertical::Center)
 }
@@ -463,38 +2793,498 @@ fn centralize_tile_content(tile_content: Text<Theme, Renderer>) -> Text<Theme, R
         .horizontal_alignment(Horizontal::Center)
         .vertical_alignment(Vertical::Center)
 }
-fn menu(game: &Game) -> widget::Container<'_, Message, cosmic::Theme> {
-    let width_box = text_input("", &game.menu.width_input).on_input(Message::InputWidth);
-    let height_box = text_input("", &game.menu.height_input).on_input(Message::InputHeight);
-    let filled_count_box =
-        text_input("", &game.menu.filled_count_input).on_input(Message::InputFilledCount);
+/// One checkbox per assist, reporting the changed set through `on_change`.
+fn assist_checkboxes<'a>(
+    assists: Assists,
+    on_change: fn(Assists) -> Message,
+) -> widget::Column<'a, Message> {
+    let selected = assists.preset().map(|preset| preset.name);
+    let presets_row = ASSIST_PRESETS
+        .iter()
+        .fold(widget::row(), |acc, preset| {
+            acc.push(
+                button(preset.name)
+                    .style(if selected == Some(preset.name) {
+                        theme::Button::Suggested
+                    } else {
+                        theme::Button::Standard
+                    })
+                    .on_press(on_change(preset.assists)),
+            )
+        })
+        .spacing(10);
+    widget::column()
+        .push(presets_row)
+        .push(widget::checkbox(
+            "Auto-cross blank lines",
+            assists.auto_cross_blank_lines,
+            move |checked| {
+                on_change(Assists {
+                    auto_cross_blank_lines: checked,
+                    ..assists
+                })
+            },
+        ))
+        .push(widget::checkbox(
+            "Block reveals in completed lines",
+            assists.guard_completed_lines,
+            move |checked| {
+                on_change(Assists {
+                    guard_completed_lines: checked,
+                    ..assists
+                })
+            },
+        ))
+        .push(widget::checkbox(
+            "Mark the ends of finished runs",
+            assists.cap_completed_runs,
+            move |checked| {
+                on_change(Assists {
+                    cap_completed_runs: checked,
+                    ..assists
+                })
+            },
+        ))
+        .spacing(10)
+}
+
+/// The start menu of kid mode, a single button starting a small board.
+fn kid_menu<'a>() -> widget::Container<'a, Message, cosmic::Theme> {
+    container(
+        widget::column()
+            .push(widget::text::title1("Let's play!"))
+            .push(
+                button(centralize_tile_content(text("Play").size(40)))
+                    .on_press(Message::StartKidGame)
+                    .style(theme::Button::Suggested)
+                    .width(240)
+                    .height(120),
+            )
+            .align_items(Alignment::Center)
+            .spacing(30),
+    )
+}
+
+#[cfg_attr(feature = "profiling", profiling::function)]
+fn menu<'a>(
+    menu: &'a Menu,
+    notice: Option<&'a str>,
+) -> widget::Container<'a, Message, cosmic::Theme> {
+    let width_box = text_input("", &menu.width_input)
+        .id(WIDTH_INPUT_ID.clone())
+        .on_input(Message::InputWidth)
+        .on_submit(Message::StartPressed);
+    let height_box = text_input("", &menu.height_input)
+        .on_input(Message::InputHeight)
+        .on_submit(Message::StartPressed);
+    let filled_count_box = text_input("", &menu.filled_count_input)
+        .on_input(Message::InputFilledCount)
+        .on_submit(Message::StartPressed);
+    let seed_box = text_input("Random", &menu.seed_input)
+        .on_input(Message::InputSeed)
+        .on_submit(Message::StartPressed);
+    let presets_row = PRESETS
+        .iter()
+        .enumerate()
+        .fold(widget::row(), |acc, (index, preset)| {
+            acc.push(
+                button(text(format!("{} (Alt+{})", preset.name, index + 1)))
+                    .on_press(Message::ApplyPreset(index)),
+            )
+        })
+        .spacing(10);
+    let saved_presets_row = menu
+        .saved_presets
+        .iter()
+        .enumerate()
+        .fold(widget::row(), |acc, (index, preset)| {
+            acc.push(
+                widget::row()
+                    .push(button(text(&preset.name)).on_press(Message::ApplySavedPreset(index)))
+                    .push(
+                        button("×")
+                            .on_press(Message::DeleteSavedPreset(index))
+                            .style(theme::Button::Destructive),
+                    ),
+            )
+        })
+        .push(
+            text_input("Preset name", &menu.preset_name)
+                .on_input(Message::InputPresetName)
+                .on_submit(Message::SavePreset)
+                .width(150),
+        )
+        .push(button("Save preset").on_press(Message::SavePreset))
+        .push(text(menu.preset_error.as_deref().unwrap_or_default()))
+        .align_items(Alignment::Center)
+        .spacing(10);
+    let error_text = text(
+        menu.error
+            .map(|error| error.to_string())
+            .or(notice.map(String::from))
+            .unwrap_or_default(),
+    );
     let start_game_button = button(centralize_tile_content(text("START")))
         .on_press(Message::StartPressed)
         .style(theme::Button::Suggested)
         .width(130)
         .height(55);
+    let week = challenge::Week::current();
+    let time_left = challenge::time_left_this_week();
+    let weekly_column = widget::column()
+        .push(
+            button(centralize_tile_content(text("Surprise me")))
+                .on_press(Message::SurpriseMe)
+                .width(180),
+        )
+        .push(
+            button(centralize_tile_content(text("Fit my screen")))
+                .on_press(Message::FitScreen)
+                .width(180),
+        )
+        .push(
+            widget::row()
+                .push(text("Tiles"))
+                .push(button("-").on_press(Message::SetFitTileSize(
+                    menu.fit_tile_size.saturating_sub(5),
+                )))
+                .push(text(format!("{} px", menu.fit_tile_size)))
+                .push(button("+").on_press(Message::SetFitTileSize(menu.fit_tile_size + 5)))
+                .align_items(Alignment::Center)
+                .spacing(10),
+        )
+        .push(
+            button(centralize_tile_content(text("Weekly challenge")))
+                .on_press(Message::StartWeekly)
+                .width(180),
+        )
+        .push(text(format!(
+            "Week {} \u{b7} {}d {}h left",
+            week.week,
+            time_left.num_days(),
+            time_left.num_hours() % 24
+        )))
+        .align_items(Alignment::Center)
+        .spacing(10);
+    let weekly_column = weekly_column.push(
+        button(centralize_tile_content(text(fl!("best-times"))))
+            .on_press(Message::ToggleContextPage(ContextPage::BestTimes))
+            .width(180),
+    );
+    let weekly_column = seasonal::unlocked_today().fold(weekly_column, |acc, index| {
+        acc.push(
+            button(centralize_tile_content(text(seasonal::PUZZLES[index].name)))
+                .on_press(Message::StartSeasonal(index))
+                .style(theme::Button::Suggested)
+                .width(180),
+        )
+    });
+    // A typed seed or share code picks the board, leaving nothing to shuffle.
+    let shuffle_button = button("Shuffle").on_press_maybe(
+        menu.seed_input
+            .trim()
+            .is_empty()
+            .then_some(Message::ShufflePreview),
+    );
+    let preview_column = widget::column()
+        .push(match &menu.preview {
+            Some(board) => preview(board),
+            None => container("")
+                .height(PREVIEW_SIZE)
+                .width(PREVIEW_SIZE)
+                .into(),
+        })
+        .push(shuffle_button)
+        .align_items(Alignment::Center)
+        .spacing(20);
+    let inputs_column = widget::column()
+        .push(presets_row)
+        .push(saved_presets_row)
+        .push(
+            widget::row()
+                .push(text("Width: "))
+                .push(width_box.width(40))
+                .align_items(Alignment::Center),
+        )
+        .push(
+            widget::row()
+                .push(text("Height: "))
+                .push(height_box.width(40))
+                .align_items(Alignment::Center),
+        )
+        .push(
+            widget::row()
+                .push(text("Filled boxes: "))
+                .push(filled_count_box.width(40))
+                .align_items(Alignment::Center),
+        )
+        .push(
+            widget::row()
+                .push(text("Seed or share code: "))
+                .push(seed_box.width(200))
+                .align_items(Alignment::Center),
+        )
+        .push(
+            widget::row()
+                .push(text("Free hints: "))
+                .push(button("-").on_press(Message::SetHints(menu.hints.saturating_sub(1))))
+                .push(text(menu.hints.to_string()))
+                .push(button("+").on_press(Message::SetHints(menu.hints + 1)))
+                .align_items(Alignment::Center)
+                .spacing(10),
+        )
+        .push(
+            widget::row()
+                .push(text("Oops protection: "))
+                .push(button("-").on_press(Message::SetForgiveness(
+                    menu.forgiveness_seconds.saturating_sub(5),
+                )))
+                .push(text(match menu.forgiveness_seconds {
+                    0 => String::from("off"),
+                    seconds => format!("{seconds}s"),
+                }))
+                .push(button("+").on_press(Message::SetForgiveness(menu.forgiveness_seconds + 5)))
+                .align_items(Alignment::Center)
+                .spacing(10),
+        )
+        .push(widget::checkbox(
+            "Relaxed (no timer)",
+            menu.relaxed,
+            Message::ToggleRelaxed,
+        ))
+        .push(widget::checkbox(
+            "No guessing needed",
+            menu.no_guessing,
+            Message::ToggleNoGuessing,
+        ))
+        .push(assist_checkboxes(menu.assists, Message::SetAssists))
+        .push(start_game_button)
+        .push(error_text)
+        .align_items(Alignment::End)
+        .spacing(20);
+    let import_row = widget::row()
+        .push(
+            text_input("Puzzle file (# and . rows)", &menu.import_path)
+                .on_input(Message::InputImportPath)
+                .on_submit(Message::ImportFile)
+                .width(250),
+        )
+        .push(button("Import file").on_press(Message::ImportFile))
+        .push(button("Paste puzzle").on_press(Message::PasteGrid))
+        .push(text(menu.import_error.as_deref().unwrap_or_default()))
+        .align_items(Alignment::Center)
+        .spacing(10);
     container(
         widget::column()
             .push(
                 widget::row()
-                    .push(text("Width: "))
-                    .push(width_box.width(40))
-                    .align_items(Alignment::Center),
-            )
-            .push(
-                widget::row()
-                    .push(text("Height: "))
-                    .push(height_box.width(40))
-                    .align_items(Alignment::Center),
-            )
-            .push(
-                widget::row()
-                    .push(text("Filled boxes: "))
-                    .push(filled_count_box.width(40))
-                    .align_items(Alignment::Center),
+                    .push(inputs_column)
+                    .push(preview_column)
+                    .push(weekly_column)
+                    .align_items(Alignment::Center)
+                    .spacing(40),
             )
-            .push(start_game_button)
-            .align_items(Alignment::End)
-            .spacing(20),
+            .push(import_row)
+            .align_items(Alignment::Center)
+            .spacing(40),
+    )
+}
+
+/// A game asked for whose board is being generated, and the seed it is generated from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Generating {
+    config: GameConfig,
+    seed: u64,
+    tile_size: Option<u16>,
+}
+
+/// Width of the names and of the bars comparing a win to earlier ones, in pixels.
+const COMPARISON_LABEL_WIDTH: u16 = 90;
+const COMPARISON_BAR_WIDTH: u16 = 160;
+
+/// Side length in pixels of the board preview shown in the menu.
+const PREVIEW_SIZE: u16 = 150;
+
+/// Room taken by the header bar, the quick settings, the buttons and the status lines.
+const PLAYFIELD_CHROME: Size = Size::new(80.0, 280.0);
+
+/// Smallest window fitting the start menu.
+const MENU_WINDOW_SIZE: Size = Size::new(860.0, 640.0);
+
+/// Sizes the playfield is laid out with.
+#[derive(Clone, Copy, Debug)]
+pub struct BoardScale {
+    /// Height of a tile.
+    pub tile: u16,
+    /// Width of a tile, narrower than its height for tall cells.
+    pub tile_width: u16,
+    /// Room for one clue number along a clue panel.
+    pub clue: u16,
+    /// Font size of clue numbers, status lines and buttons.
+    pub text: u16,
+}
+
+impl BoardScale {
+    pub const NORMAL: Self = BoardScale {
+        tile: 50,
+        tile_width: 50,
+        clue: 20,
+        text: 14,
+    };
+    pub const KID: Self = BoardScale {
+        tile: 90,
+        tile_width: 90,
+        clue: 30,
+        text: 20,
+    };
+    /// Large print, bigger again than kid mode; the board scrolls when it no longer fits.
+    pub const LARGE: Self = BoardScale {
+        tile: 100,
+        tile_width: 100,
+        clue: 40,
+        text: 28,
+    };
+
+    /// Length of a clue panel for lines of `length` tiles, which hold at most
+    /// half as many clues, rounded up.
+    fn clue_panel(self, length: usize) -> u16 {
+        (length as u16).div_ceil(2) * self.clue
+    }
+
+    /// This scale with cells of the given shape.
+    pub fn with_shape(self, shape: CellShape) -> Self {
+        BoardScale {
+            tile_width: self.tile * shape.width_percent() / 100,
+            ..self
+        }
+    }
+
+    /// Width of a row of `length` tiles with the 2 px spacing around them.
+    fn board_width(self, length: usize) -> f32 {
+        ((self.tile_width + 2) as usize * length + 2) as f32
+    }
+
+    /// Height of a column of `length` tiles with the 2 px spacing around them.
+    fn board_height(self, length: usize) -> f32 {
+        ((self.tile + 2) as usize * length + 2) as f32
+    }
+}
+
+/// Proportions of a tile. Tall cells, as some nonogram books print them, also let wide
+/// boards fit on wide screens.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CellShape {
+    #[default]
+    Square,
+    Tall,
+    Taller,
+}
+
+impl CellShape {
+    pub const ALL: [CellShape; 3] = [CellShape::Square, CellShape::Tall, CellShape::Taller];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CellShape::Square => "Square",
+            CellShape::Tall => "Tall",
+            CellShape::Taller => "Taller",
+        }
+    }
+
+    /// Width of a tile in percent of its height.
+    fn width_percent(self) -> u16 {
+        match self {
+            CellShape::Square => 100,
+            CellShape::Tall => 85,
+            CellShape::Taller => 70,
+        }
+    }
+}
+
+/// Font of the clue numbers. Digits in a monospace font all take the same width, so
+/// multi-digit clues line up in the column panels.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ClueFont {
+    #[default]
+    Theme,
+    Monospace,
+}
+
+impl ClueFont {
+    pub const ALL: [ClueFont; 2] = [ClueFont::Theme, ClueFont::Monospace];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ClueFont::Theme => "Theme font",
+            ClueFont::Monospace => "Monospace",
+        }
+    }
+
+    /// A clue number set `size` large in this font.
+    fn text(self, count: u8, size: u16) -> Text<'static, Theme, Renderer> {
+        let number = text(count.to_string()).size(size);
+        match self {
+            ClueFont::Theme => number,
+            ClueFont::Monospace => number.font(Font::MONOSPACE),
+        }
+    }
+}
+
+/// A window size showing a whole `width`×`height` board with its clues and controls,
+/// so nothing is clipped when the app opens.
+pub fn window_size_for_board(width: usize, height: usize, scale: BoardScale) -> Size {
+    let clues = Size::new(
+        f32::from(scale.clue_panel(width)),
+        f32::from(scale.clue_panel(height)),
+    );
+    let board = Size::new(scale.board_width(width), scale.board_height(height));
+    Size::new(
+        (clues.width + board.width + PLAYFIELD_CHROME.width).max(MENU_WINDOW_SIZE.width),
+        (clues.height + board.height + PLAYFIELD_CHROME.height).max(MENU_WINDOW_SIZE.height),
+    )
+}
+
+/// The largest board whose tiles, clues and controls all fit in a window of size
+/// `window` at `scale`, the inverse of [`window_size_for_board`].
+fn board_fitting(window: Size, scale: BoardScale) -> (usize, usize) {
+    let largest = |tiles: fn(BoardScale, usize) -> f32, room: f32| {
+        (1..=MAX_DIMENSION)
+            .take_while(|&length| {
+                f32::from(scale.clue_panel(length)) + tiles(scale, length) <= room
+            })
+            .last()
+            .unwrap_or(1)
+    };
+    (
+        largest(
+            BoardScale::board_width,
+            window.width - PLAYFIELD_CHROME.width,
+        ),
+        largest(
+            BoardScale::board_height,
+            window.height - PLAYFIELD_CHROME.height,
+        ),
     )
 }
+
+fn preview(board: &Board) -> Element<'_, Message> {
+    let tile_size = (PREVIEW_SIZE / board.width.max(board.height) as u16).max(1);
+    (0..board.height)
+        .fold(widget::column(), |acc, row| {
+            let new_row = (0..board.width).fold(widget::row(), |acc2, column| {
+                let style = if board.board_vec[pair_to_index(row, column, board.width)].empty {
+                    theme::Container::custom(gray1theme)
+                } else {
+                    theme::Container::custom(blacktheme)
+                };
+                acc2.push(
+                    container("")
+                        .style(style)
+                        .width(tile_size)
+                        .height(tile_size),
+                )
+            });
+            acc.push(new_row)
+        })
+        .into()
+}
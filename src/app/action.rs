@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+
+use cosmic::iced::keyboard::{Key, Modifiers};
+use cosmic::widget::menu::{
+    self,
+    key_bind::{KeyBind, Modifier},
+};
+
+use super::game::{Game, Winstate};
+use super::{ContextPage, Message, Picross};
+use crate::fl;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MenuAction {
+    About,
+    Bookmarks,
+    BestTimes,
+    Statistics,
+    WhatsNew,
+    Appearance,
+    Saving,
+    ToggleProgressInTitle,
+    ToggleCrosshair,
+    ToggleBreakReminders,
+    ToggleEnforceBreaks,
+    ToggleKidMode,
+    LockKidMode,
+    ToggleLargePrint,
+    ToggleStuckNudges,
+    ToggleSaveNotification,
+    ToggleStreamerMode,
+    ToggleStreamOverlay,
+    TogglePause,
+    Undo,
+    Redo,
+    Lap,
+    Restart,
+}
+
+impl menu::action::MenuAction for MenuAction {
+    type Message = Message;
+
+    fn message(&self) -> Self::Message {
+        match self {
+            MenuAction::About => Message::ToggleContextPage(ContextPage::About),
+            MenuAction::Bookmarks => Message::ToggleContextPage(ContextPage::Bookmarks),
+            MenuAction::BestTimes => Message::ToggleContextPage(ContextPage::BestTimes),
+            MenuAction::Statistics => Message::ToggleContextPage(ContextPage::Statistics),
+            MenuAction::WhatsNew => Message::ToggleContextPage(ContextPage::WhatsNew),
+            MenuAction::Appearance => Message::ToggleContextPage(ContextPage::Appearance),
+            MenuAction::Saving => Message::ToggleContextPage(ContextPage::Saving),
+            MenuAction::ToggleProgressInTitle => Message::ToggleProgressInTitle,
+            MenuAction::ToggleCrosshair => Message::ToggleCrosshair,
+            MenuAction::ToggleBreakReminders => Message::ToggleBreakReminders,
+            MenuAction::ToggleEnforceBreaks => Message::ToggleEnforceBreaks,
+            MenuAction::ToggleKidMode => Message::ToggleKidMode,
+            MenuAction::LockKidMode => Message::LockKidMode,
+            MenuAction::ToggleLargePrint => Message::ToggleLargePrint,
+            MenuAction::ToggleStuckNudges => Message::ToggleStuckNudges,
+            MenuAction::ToggleSaveNotification => Message::ToggleSaveNotification,
+            MenuAction::ToggleStreamerMode => Message::ToggleStreamerMode,
+            MenuAction::ToggleStreamOverlay => Message::ToggleStreamOverlay,
+            MenuAction::TogglePause => Message::TogglePause,
+            MenuAction::Undo => Message::Undo,
+            MenuAction::Redo => Message::Redo,
+            MenuAction::Lap => Message::Lap,
+            MenuAction::Restart => Message::Restart,
+        }
+    }
+}
+
+/// How an action is presented wherever it is listed.
+#[derive(Clone, Copy)]
+pub enum ActionKind {
+    Button,
+    /// An on/off option, checked when the function returns `true`.
+    Toggle(fn(&Picross) -> bool),
+}
+
+/// A key combination triggering an action from anywhere in the app.
+pub struct Shortcut {
+    pub modifiers: &'static [Modifier],
+    pub key: &'static str,
+}
+
+/// Everything the menu bar, the quick settings and the key bindings know about an action.
+pub struct ActionSpec {
+    pub action: MenuAction,
+    pub label: fn() -> String,
+    pub kind: ActionKind,
+    pub shortcut: Option<Shortcut>,
+    /// Whether the action can be used in the current state of the app.
+    pub available: fn(&Picross) -> bool,
+}
+
+/// Every action, in the order the View menu lists them.
+pub const ACTIONS: &[ActionSpec] = &[
+    ActionSpec {
+        action: MenuAction::ToggleProgressInTitle,
+        label: || fl!("progress-in-title"),
+        kind: ActionKind::Toggle(|app| app.progress_in_title),
+        shortcut: None,
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::ToggleCrosshair,
+        label: || fl!("crosshair-guides"),
+        kind: ActionKind::Toggle(|app| app.crosshair),
+        shortcut: Some(Shortcut {
+            modifiers: &[Modifier::Ctrl],
+            key: "g",
+        }),
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::ToggleBreakReminders,
+        label: || fl!("break-reminders"),
+        kind: ActionKind::Toggle(|app| app.tilt.remind),
+        shortcut: None,
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::ToggleEnforceBreaks,
+        label: || fl!("enforce-breaks"),
+        kind: ActionKind::Toggle(|app| app.tilt.enforce),
+        shortcut: None,
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::ToggleKidMode,
+        label: || fl!("kid-mode"),
+        kind: ActionKind::Toggle(|app| app.kid_mode),
+        shortcut: None,
+        available: |app| !app.kid_mode_fixed(),
+    },
+    ActionSpec {
+        action: MenuAction::LockKidMode,
+        label: || fl!("lock-kid-mode"),
+        kind: ActionKind::Toggle(|app| app.kid_mode_locked),
+        shortcut: None,
+        // Only a settings change outside the app lifts the lock.
+        available: |app| !app.kid_mode_locked,
+    },
+    ActionSpec {
+        action: MenuAction::ToggleLargePrint,
+        label: || fl!("large-print"),
+        kind: ActionKind::Toggle(|app| app.large_print),
+        shortcut: None,
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::ToggleStuckNudges,
+        label: || fl!("stuck-nudges"),
+        kind: ActionKind::Toggle(|app| app.stuck_nudges),
+        shortcut: None,
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::ToggleSaveNotification,
+        label: || fl!("save-notification"),
+        kind: ActionKind::Toggle(|app| app.save_notification),
+        shortcut: None,
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::ToggleStreamerMode,
+        label: || fl!("streamer-mode"),
+        kind: ActionKind::Toggle(|app| app.streamer_mode),
+        shortcut: None,
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::ToggleStreamOverlay,
+        label: || fl!("stream-overlay"),
+        kind: ActionKind::Toggle(|app| app.stream_overlay),
+        shortcut: None,
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::TogglePause,
+        label: || fl!("pause"),
+        kind: ActionKind::Toggle(|app| app.game.as_ref().is_some_and(|game| game.paused)),
+        shortcut: Some(Shortcut {
+            modifiers: &[],
+            key: "p",
+        }),
+        available: |app| {
+            app.game
+                .as_ref()
+                .is_some_and(|game| matches!(game.winstate, Winstate::InProgress))
+        },
+    },
+    ActionSpec {
+        action: MenuAction::Undo,
+        label: || fl!("undo"),
+        kind: ActionKind::Button,
+        shortcut: Some(Shortcut {
+            modifiers: &[Modifier::Ctrl],
+            key: "z",
+        }),
+        available: |app| app.game.as_ref().is_some_and(Game::can_undo),
+    },
+    ActionSpec {
+        action: MenuAction::Redo,
+        label: || fl!("redo"),
+        kind: ActionKind::Button,
+        // Shift turns the key into an upper case character.
+        shortcut: Some(Shortcut {
+            modifiers: &[Modifier::Ctrl, Modifier::Shift],
+            key: "Z",
+        }),
+        available: |app| app.game.as_ref().is_some_and(Game::can_redo),
+    },
+    ActionSpec {
+        action: MenuAction::Lap,
+        label: || fl!("lap"),
+        kind: ActionKind::Button,
+        // Typing into the lap label doesn't reach the shortcut, so a bare key is safe.
+        shortcut: Some(Shortcut {
+            modifiers: &[],
+            key: "l",
+        }),
+        available: |app| app.game.as_ref().is_some_and(Game::can_lap),
+    },
+    ActionSpec {
+        action: MenuAction::Restart,
+        label: || fl!("restart"),
+        kind: ActionKind::Button,
+        shortcut: Some(Shortcut {
+            modifiers: &[Modifier::Ctrl],
+            key: "r",
+        }),
+        available: |app| app.game.is_some(),
+    },
+    ActionSpec {
+        action: MenuAction::Appearance,
+        label: || fl!("appearance"),
+        kind: ActionKind::Button,
+        shortcut: None,
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::Saving,
+        label: || fl!("saving"),
+        kind: ActionKind::Button,
+        shortcut: None,
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::Bookmarks,
+        label: || fl!("bookmarks"),
+        kind: ActionKind::Button,
+        shortcut: Some(Shortcut {
+            modifiers: &[Modifier::Ctrl],
+            key: "b",
+        }),
+        available: |app| app.game.is_some(),
+    },
+    ActionSpec {
+        action: MenuAction::BestTimes,
+        label: || fl!("best-times"),
+        kind: ActionKind::Button,
+        shortcut: None,
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::Statistics,
+        label: || fl!("statistics"),
+        kind: ActionKind::Button,
+        shortcut: None,
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::WhatsNew,
+        label: || fl!("whats-new"),
+        kind: ActionKind::Button,
+        shortcut: None,
+        available: |_| true,
+    },
+    ActionSpec {
+        action: MenuAction::About,
+        label: || fl!("about"),
+        kind: ActionKind::Button,
+        shortcut: None,
+        available: |_| true,
+    },
+];
+
+/// The default key bindings of every action that has a shortcut.
+pub fn key_binds() -> HashMap<KeyBind, MenuAction> {
+    ACTIONS
+        .iter()
+        .filter_map(|spec| {
+            let shortcut = spec.shortcut.as_ref()?;
+            let key_bind = KeyBind {
+                modifiers: shortcut.modifiers.to_vec(),
+                key: Key::Character(shortcut.key.into()),
+            };
+            Some((key_bind, spec.action))
+        })
+        .collect()
+}
+
+/// The View menu entries, with toggles first and a divider before the pages.
+pub fn menu_items(app: &Picross) -> Vec<menu::Item<MenuAction, String>> {
+    let mut items = Vec::new();
+    let mut after_toggle = false;
+    for spec in ACTIONS {
+        let label = (spec.label)();
+        match spec.kind {
+            ActionKind::Toggle(checked) => {
+                after_toggle = true;
+                items.push(if (spec.available)(app) {
+                    menu::Item::CheckBox(label, checked(app), spec.action)
+                } else {
+                    menu::Item::ButtonDisabled(label, spec.action)
+                });
+            }
+            ActionKind::Button => {
+                if std::mem::take(&mut after_toggle) {
+                    items.push(menu::Item::Divider);
+                }
+                items.push(if (spec.available)(app) {
+                    menu::Item::Button(label, spec.action)
+                } else {
+                    menu::Item::ButtonDisabled(label, spec.action)
+                });
+            }
+        }
+    }
+    items
+}
+
+/// The action bound to `key` pressed with `modifiers`, if it can be used right now.
+pub fn for_key(app: &Picross, modifiers: Modifiers, key: &Key) -> Option<MenuAction> {
+    app.key_binds
+        .iter()
+        .find(|(key_bind, _)| key_bind.matches(modifiers, key))
+        .map(|(_, &action)| action)
+        .filter(|&action| {
+            ACTIONS
+                .iter()
+                .any(|spec| spec.action == action && (spec.available)(app))
+        })
+}
@@ -0,0 +1,47 @@
+use chrono::{Datelike, Local, TimeDelta};
+
+use super::game::GameConfig;
+
+const WEEKLY_WIDTH: usize = 20;
+const WEEKLY_HEIGHT: usize = 20;
+const WEEKLY_FILLED_COUNT: usize = 240;
+
+/// The ISO week a weekly challenge belongs to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Week {
+    pub year: i32,
+    pub week: u32,
+}
+
+impl Week {
+    pub fn current() -> Self {
+        let iso_week = Local::now().iso_week();
+        Week {
+            year: iso_week.year(),
+            week: iso_week.week(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.year as u64 * 100 + self.week as u64
+    }
+
+    /// The fixed rule set shared by everyone playing this week's challenge.
+    pub fn config(&self) -> GameConfig {
+        let mut config = GameConfig::new(WEEKLY_WIDTH, WEEKLY_HEIGHT, WEEKLY_FILLED_COUNT)
+            .expect("weekly challenge dimensions are valid");
+        config.seed = Some(self.seed());
+        config.no_guessing = true;
+        config
+    }
+}
+
+/// Time left until the current ISO week ends at the next local midnight on Monday.
+pub fn time_left_this_week() -> TimeDelta {
+    let now = Local::now().naive_local();
+    let days_left = 7 - now.weekday().num_days_from_monday();
+    let week_end = (now.date() + TimeDelta::days(days_left.into()))
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time");
+    week_end - now
+}
@@ -0,0 +1,48 @@
+use super::ContextPage;
+
+/// Where a changelog entry takes the player when they follow it.
+#[derive(Clone, Copy, Debug)]
+pub enum ChangeLink {
+    Page(ContextPage),
+    StartMenu,
+}
+
+pub struct Change {
+    pub text: &'static str,
+    pub link: Option<ChangeLink>,
+}
+
+pub struct Release {
+    pub version: &'static str,
+    pub changes: &'static [Change],
+}
+
+/// Release notes, newest first.
+pub const RELEASES: &[Release] = &[Release {
+    version: "0.1.0",
+    changes: &[
+        Change {
+            text: "Weekly challenge and seasonal puzzles in the start menu",
+            link: Some(ChangeLink::StartMenu),
+        },
+        Change {
+            text: "Share codes and seeds to replay the same board and rules",
+            link: Some(ChangeLink::StartMenu),
+        },
+        Change {
+            text: "Import puzzles drawn with # and . from a file or the clipboard",
+            link: Some(ChangeLink::StartMenu),
+        },
+        Change {
+            text: "Bookmarks and notes for the board you are playing",
+            link: Some(ChangeLink::Page(ContextPage::Bookmarks)),
+        },
+        Change {
+            text: "Auto-cross blank lines assist and quick settings over the board",
+            link: None,
+        },
+    ],
+}];
+
+/// The version of this build, compared against the last one the player ran.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
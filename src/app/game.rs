@@ -1,77 +1,796 @@
-use rand::thread_rng;
+use std::time::{Duration, Instant};
+
+use rand::seq::IteratorRandom;
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+pub use clock::{Clock, Lap};
+pub use event::{GameEvent, Line};
+use judge::Judge;
+pub use solver::Solutions;
+
+mod clock;
+mod event;
+mod integrity;
+mod judge;
+mod seed_text;
+pub mod solver;
+mod undo;
 
 pub const fn pair_to_index(row: usize, column: usize, width: usize) -> usize {
     row * width + column
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Game {
+    pub config: GameConfig,
     pub board: Board,
-    pub menu: Menu,
     pub winstate: Winstate,
+    /// The seed the board was generated from.
+    #[serde(with = "seed_text")]
+    pub seed: u64,
+    /// Set for hand-made puzzles, which are replayed on reset instead of regenerated.
+    pub fixed: bool,
+    pub bookmarks: Vec<Bookmark>,
+    /// A free-form note the player attached to this puzzle.
+    pub note: String,
+    /// When a filled tile was last revealed, which opens the forgiveness window.
+    #[serde(skip)]
+    pub last_correct_reveal: Option<Instant>,
+    /// Whether this game's one forgiven mistake has been used up.
+    pub mistake_forgiven: bool,
+    /// Correct reveals in a row, without marks, mistakes or long pauses in between.
+    pub combo: u32,
+    pub best_combo: u32,
+    /// How many times this board has been played, counting the current try.
+    pub attempts: u32,
+    /// Hints taken so far, counting those past the config's budget and the tutor's
+    /// explanations.
+    pub hints_used: u8,
+    /// Time spent solving, which stops once the game is over.
+    #[serde(default)]
+    pub clock: Clock,
+    /// Moments the player marked while solving, in the order they were taken.
+    #[serde(default)]
+    pub laps: Vec<Lap>,
+    /// What generation could establish about the clues, for boards it made.
+    #[serde(default)]
+    pub solvability: Solvability,
+    /// Tile height, in pixels, of a board picked to fill the window, which it is drawn at
+    /// instead of the usual size.
+    #[serde(default)]
+    pub tile_size: Option<u16>,
+    /// Whether the game is paused, its clock standing still and no moves allowed.
+    #[serde(default)]
+    pub paused: bool,
+    /// Whether this game's win or loss has been counted, which only ever happens once,
+    /// even when a relaxed game is lost, taken back and then won.
+    #[serde(default)]
+    pub outcome_counted: bool,
+    /// Moves that can be taken back and played again, kept in saves too.
+    #[serde(default)]
+    history: undo::History,
 }
 
+/// Thinking for longer than this between reveals ends the combo.
+pub const COMBO_PAUSE: Duration = Duration::from_secs(5);
+
+/// Time added to the clock for every hint taken once the free ones are used up.
+pub const HINT_PENALTY: Duration = Duration::from_secs(30);
+
 impl Game {
-    pub fn new() -> Self {
-        Game {
-            board: Board::new(10, 10, 40),
-            menu: Menu {
-                width_input: String::from("10"),
-                height_input: String::from("10"),
-                filled_count_input: String::from("65"),
-                start_pressed: false,
-            },
+    pub fn with_config(config: GameConfig) -> Self {
+        let seed = config.pick_seed();
+        let (board, solvability) = Board::for_config(&config, seed);
+        Self::with_generated(config, seed, board, solvability)
+    }
+    /// A game with `config` on the board [`Board::for_config`] generated for `seed`.
+    pub fn with_generated(
+        config: GameConfig,
+        seed: u64,
+        board: Board,
+        solvability: Solvability,
+    ) -> Self {
+        let mut game = Game {
+            board,
+            config,
+            winstate: Winstate::InProgress,
+            seed,
+            fixed: false,
+            bookmarks: Vec::new(),
+            note: String::new(),
+            last_correct_reveal: None,
+            mistake_forgiven: false,
+            combo: 0,
+            best_combo: 0,
+            attempts: 1,
+            hints_used: 0,
+            clock: Clock::started(),
+            laps: Vec::new(),
+            solvability,
+            tile_size: None,
+            paused: false,
+            outcome_counted: false,
+            history: undo::History::default(),
+        };
+        game.apply_start_assists();
+        game
+    }
+    pub fn with_board(config: GameConfig, board: Board) -> Self {
+        let mut game = Game {
+            board,
+            config,
             winstate: Winstate::InProgress,
+            seed: 0,
+            fixed: true,
+            bookmarks: Vec::new(),
+            note: String::new(),
+            last_correct_reveal: None,
+            mistake_forgiven: false,
+            combo: 0,
+            best_combo: 0,
+            attempts: 1,
+            hints_used: 0,
+            clock: Clock::started(),
+            laps: Vec::new(),
+            // Hand-made boards are checked where they are imported.
+            solvability: Solvability::Unproven,
+            tile_size: None,
+            paused: false,
+            outcome_counted: false,
+            history: undo::History::default(),
+        };
+        game.apply_start_assists();
+        game
+    }
+    pub fn reset(&mut self) {
+        if self.fixed {
+            self.restart();
+        } else {
+            *self = Game {
+                tile_size: self.tile_size,
+                ..Self::with_config(self.config)
+            };
         }
     }
-    pub fn wincheck(&mut self) {
-        if self.board.board_vec.iter().all(|&tile| {
-            (tile.empty == false && tile.hidden == false)
-                || (tile.empty == true && tile.hidden == true)
-        }) {
-            self.winstate = Winstate::Won;
-            (0..self.board.width * self.board.height)
-                .for_each(|id| self.board.board_vec[id].hidden = false)
-        } else if self
+    /// Plays the same board again from scratch as another attempt.
+    pub fn restart(&mut self) {
+        // Replaying a puzzle keeps what the player wrote about it.
+        let mut board = self.board.clone();
+        board.clear_progress();
+        *self = Game {
+            seed: self.seed,
+            fixed: self.fixed,
+            bookmarks: std::mem::take(&mut self.bookmarks),
+            note: std::mem::take(&mut self.note),
+            attempts: self.attempts + 1,
+            solvability: self.solvability,
+            tile_size: self.tile_size,
+            ..Self::with_board(self.config, board)
+        };
+    }
+    pub fn reveal(&mut self, id: usize) -> Vec<GameEvent> {
+        if self.paused {
+            return Vec::new();
+        }
+        self.undoable(|game| game.reveal_tile(id))
+    }
+    fn reveal_tile(&mut self, id: usize) -> Vec<GameEvent> {
+        if !self.board.board_vec[id].hidden {
+            return Vec::new();
+        }
+        if self.config.assists.guard_completed_lines
+            && self
+                .board
+                .lines_through(id)
+                .into_iter()
+                .any(|line| self.board.is_line_completed(line))
+        {
+            return vec![GameEvent::RevealBlocked(id)];
+        }
+        if self.board.board_vec[id].empty && self.can_forgive_mistake() {
+            self.board.board_vec[id].marked = true;
+            self.mistake_forgiven = true;
+            self.combo = 0;
+            return vec![GameEvent::MistakeForgiven(id)];
+        }
+        let tile = &mut self.board.board_vec[id];
+        tile.hidden = false;
+        tile.marked = false;
+        let mut events = if tile.empty {
+            self.combo = 0;
+            vec![GameEvent::MistakeMade(id)]
+        } else {
+            let now = Instant::now();
+            if self
+                .last_correct_reveal
+                .is_some_and(|revealed_at| now - revealed_at > COMBO_PAUSE)
+            {
+                self.combo = 0;
+            }
+            self.combo += 1;
+            self.best_combo = self.best_combo.max(self.combo);
+            self.last_correct_reveal = Some(now);
+            let mut events: Vec<GameEvent> = std::iter::once(GameEvent::TileRevealed(id))
+                .chain(
+                    self.board
+                        .lines_through(id)
+                        .into_iter()
+                        .filter(|&line| self.board.is_line_completed(line))
+                        .map(GameEvent::LineCompleted),
+                )
+                .collect();
+            if self.config.assists.cap_completed_runs {
+                events.extend(self.cap_completed_runs(id));
+            }
+            events
+        };
+        events.extend(self.wincheck_events());
+        events
+    }
+    /// Marks the tiles on both ends of the revealed runs through `id` that are as long as
+    /// the longest clue of their line. Such a run can't grow, so its ends must be empty,
+    /// and nothing is given away that the clues don't already tell.
+    fn cap_completed_runs(&mut self, id: usize) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        for line in self.board.lines_through(id) {
+            let Some(&longest) = self.board.clues(line).iter().max() else {
+                continue;
+            };
+            let ids: Vec<usize> = self.board.line_ids(line).collect();
+            let Some(position) = ids.iter().position(|&other| other == id) else {
+                continue;
+            };
+            let revealed = |index: &usize| {
+                let tile = self.board.board_vec[ids[*index]];
+                !tile.hidden && !tile.empty
+            };
+            let start = (0..position)
+                .rev()
+                .take_while(revealed)
+                .last()
+                .unwrap_or(position);
+            let end = (position + 1..ids.len())
+                .take_while(revealed)
+                .last()
+                .unwrap_or(position);
+            if end - start + 1 != usize::from(longest) {
+                continue;
+            }
+            let caps = [
+                start.checked_sub(1),
+                Some(end + 1).filter(|&cap| cap < ids.len()),
+            ];
+            for cap in caps.into_iter().flatten().map(|cap| ids[cap]) {
+                let tile = &mut self.board.board_vec[cap];
+                if tile.hidden && !tile.marked {
+                    tile.marked = true;
+                    events.push(GameEvent::TileMarked {
+                        id: cap,
+                        marked: true,
+                    });
+                }
+            }
+        }
+        events
+    }
+    /// Whether laps can be taken, which needs a timed game still in play.
+    pub fn can_lap(&self) -> bool {
+        !self.config.relaxed && matches!(self.winstate, Winstate::InProgress)
+    }
+    pub fn lap(&mut self, label: String) {
+        if self.can_lap() {
+            self.laps.push(Lap {
+                at: self.clock.elapsed(),
+                label,
+            });
+        }
+    }
+    /// Stops the clock and holds off moves until [`Game::resume`]. Only a game in play
+    /// can be paused.
+    pub fn pause(&mut self) {
+        self.pause_since(Instant::now());
+    }
+    /// Pauses the game as if that had happened at `since`, taking the time after it
+    /// back off the clock.
+    pub fn pause_since(&mut self, since: Instant) {
+        if let Winstate::InProgress = self.winstate {
+            self.paused = true;
+            self.clock.stop_at(since);
+        }
+    }
+    pub fn resume(&mut self) {
+        if self.paused {
+            self.paused = false;
+            self.clock.set_running(true);
+        }
+    }
+    pub fn hints_left(&self) -> u8 {
+        self.config.hints.saturating_sub(self.hints_used)
+    }
+    /// What the next hint costs on the clock, nothing while free hints are left.
+    pub fn hint_penalty(&self) -> Duration {
+        if self.hints_left() > 0 {
+            Duration::ZERO
+        } else {
+            HINT_PENALTY
+        }
+    }
+    /// Counts a hint as taken, adding [`HINT_PENALTY`] to the clock once the free hints
+    /// are used up.
+    pub fn charge_hint(&mut self) {
+        self.clock.add(self.hint_penalty());
+        self.hints_used = self.hints_used.saturating_add(1);
+    }
+    /// Spends a hint on revealing or marking the next tile that follows from the clues
+    /// and what the player has settled, adding [`HINT_PENALTY`] to the clock once the
+    /// free hints are used up.
+    pub fn hint(&mut self) -> Vec<GameEvent> {
+        if self.paused || !matches!(self.winstate, Winstate::InProgress) {
+            return Vec::new();
+        }
+        // A deduction that disagrees with the board would cost the player the game, so
+        // it is never acted on, however it came about.
+        let Some(deduction) = self
+            .board
+            .next_deduction()
+            .filter(|deduction| deduction.filled != self.board.board_vec[deduction.id].empty)
+        else {
+            return vec![GameEvent::HintUnavailable];
+        };
+        self.charge_hint();
+        let mut events = vec![GameEvent::HintUsed(deduction.id)];
+        events.extend(if deduction.filled {
+            self.reveal(deduction.id)
+        } else {
+            self.toggle_mark(deduction.id)
+        });
+        // A hinted reveal is no achievement of the player's.
+        self.combo = 0;
+        events
+    }
+    /// Whether a wrong reveal right now falls within the forgiveness window.
+    fn can_forgive_mistake(&self) -> bool {
+        let window = Duration::from_secs(self.config.forgiveness_seconds.into());
+        !self.mistake_forgiven
+            && !window.is_zero()
+            && self
+                .last_correct_reveal
+                .is_some_and(|revealed_at| revealed_at.elapsed() <= window)
+    }
+    pub fn toggle_mark(&mut self, id: usize) -> Vec<GameEvent> {
+        if self.paused {
+            return Vec::new();
+        }
+        self.undoable(|game| game.toggle_tile_mark(id))
+    }
+    fn toggle_tile_mark(&mut self, id: usize) -> Vec<GameEvent> {
+        // There's nothing left to guess about a revealed tile.
+        if !self.board.board_vec[id].hidden {
+            return Vec::new();
+        }
+        self.combo = 0;
+        let marked = &mut self.board.board_vec[id].marked;
+        *marked = !*marked;
+        let mut events = vec![GameEvent::TileMarked {
+            id,
+            marked: *marked,
+        }];
+        events.extend(self.wincheck_events());
+        events
+    }
+    /// Runs [`Game::wincheck`], reporting the end of the game if it happened just now.
+    fn wincheck_events(&mut self) -> Option<GameEvent> {
+        let was_in_progress = matches!(self.winstate, Winstate::InProgress);
+        self.wincheck();
+        match self.winstate {
+            Winstate::Won if was_in_progress => Some(GameEvent::GameWon),
+            Winstate::Lost if was_in_progress => Some(GameEvent::GameLost),
+            _ => None,
+        }
+    }
+    /// Changes the assists of a running game, applying newly enabled ones right away as
+    /// a move of their own, which can be taken back.
+    pub fn set_assists(&mut self, assists: Assists) {
+        self.config.assists = assists;
+        if let Winstate::InProgress = self.winstate {
+            self.undoable(|game| {
+                game.apply_start_assists();
+                Vec::new()
+            });
+        }
+    }
+    fn apply_start_assists(&mut self) {
+        if self.config.assists.auto_cross_blank_lines {
+            self.board.resolve_trivial_lines();
+            self.wincheck();
+        }
+    }
+    pub fn add_bookmark(&mut self, name: String) {
+        let ids_where = |predicate: fn(&Tile) -> bool| {
+            self.board
+                .board_vec
+                .iter()
+                .enumerate()
+                .filter(|(_, tile)| predicate(tile))
+                .map(|(id, _)| id)
+                .collect()
+        };
+        let bookmark = Bookmark {
+            name,
+            revealed: ids_where(|tile| !tile.hidden),
+            marked: ids_where(|tile| tile.marked),
+        };
+        self.bookmarks.push(bookmark);
+    }
+    pub fn restore_bookmark(&mut self, index: usize) {
+        let Some(bookmark) = self.bookmarks.get(index) else {
+            return;
+        };
+        self.board.clear_progress();
+        self.history = undo::History::default();
+        bookmark
+            .revealed
+            .iter()
+            .for_each(|&id| self.board.board_vec[id].hidden = false);
+        bookmark
+            .marked
+            .iter()
+            .for_each(|&id| self.board.board_vec[id].marked = true);
+        self.winstate = Winstate::InProgress;
+        self.wincheck();
+    }
+    /// A code reproducing this game's board and rules, unless the board is hand-made.
+    pub fn share_code(&self) -> Option<String> {
+        (!self.fixed).then(|| self.config.share_code(self.seed))
+    }
+    /// Percentage of filled tiles revealed so far.
+    pub fn progress(&self) -> u8 {
+        let revealed = self
             .board
             .board_vec
             .iter()
-            .any(|&tile| tile.empty == true && tile.hidden == false)
-        {
-            self.winstate = Winstate::Lost
+            .filter(|tile| !tile.empty && !tile.hidden)
+            .count();
+        // A board without filled tiles has nothing left to reveal.
+        (revealed * 100)
+            .checked_div(self.board.filled_count)
+            .map_or(100, |percent| percent as u8)
+    }
+    /// Lets the [`Judge`] decide a game still in play, revealing the whole board once
+    /// it is won. A finished game stays finished until its progress is cleared, and its
+    /// clock only runs while it is in play and not paused.
+    #[cfg_attr(feature = "profiling", profiling::function)]
+    pub fn wincheck(&mut self) {
+        if let Winstate::InProgress = self.winstate {
+            self.winstate = Judge::new(&self.config).judge(&self.board);
+            if let Winstate::Won = self.winstate {
+                self.board
+                    .board_vec
+                    .iter_mut()
+                    .for_each(|tile| tile.hidden = false);
+            }
+        }
+        self.clock
+            .set_running(matches!(self.winstate, Winstate::InProgress) && !self.paused);
+    }
+}
+
+/// A named snapshot of the player's progress, stored as the tiles that differ
+/// from a fresh board.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub revealed: Vec<usize>,
+    pub marked: Vec<usize>,
+}
+
+/// Validated parameters for starting a new game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameConfig {
+    pub width: usize,
+    pub height: usize,
+    pub filled_count: usize,
+    /// Relaxed games hide the timer and are kept out of time-based records.
+    pub relaxed: bool,
+    /// Number of free hints, at most [`MAX_HINTS`].
+    pub hints: u8,
+    /// Generates the same board every time when set.
+    #[serde(default, with = "seed_text::optional")]
+    pub seed: Option<u64>,
+    pub assists: Assists,
+    /// A wrong reveal this many seconds after a correct one is forgiven once per
+    /// game, `0` turns it off.
+    pub forgiveness_seconds: u8,
+    /// Mistakes stay on the board but never lose the game.
+    pub no_loss: bool,
+    /// The board can be solved by line logic alone, without guessing.
+    #[serde(default)]
+    pub no_guessing: bool,
+}
+
+pub const MAX_FORGIVENESS_SECONDS: u8 = 30;
+
+/// Optional helpers that take busywork off the player.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Assists {
+    /// Marks lines without clues and reveals lines filled end to end when the game starts.
+    pub auto_cross_blank_lines: bool,
+    /// Blocks reveals in lines whose filled tiles are all revealed already.
+    pub guard_completed_lines: bool,
+    /// Marks both ends of a revealed run as soon as it is as long as its line's longest clue.
+    #[serde(default)]
+    pub cap_completed_runs: bool,
+}
+
+impl Assists {
+    /// The preset these assists amount to, if they match one exactly.
+    pub fn preset(self) -> Option<&'static AssistPreset> {
+        ASSIST_PRESETS.iter().find(|preset| preset.assists == self)
+    }
+}
+
+/// A named set of assists selectable in one click.
+pub struct AssistPreset {
+    pub name: &'static str,
+    pub assists: Assists,
+}
+
+pub const ASSIST_PRESETS: &[AssistPreset] = &[
+    AssistPreset {
+        name: "Beginner",
+        assists: Assists {
+            auto_cross_blank_lines: true,
+            guard_completed_lines: true,
+            cap_completed_runs: true,
+        },
+    },
+    AssistPreset {
+        name: "Standard",
+        assists: Assists {
+            auto_cross_blank_lines: true,
+            guard_completed_lines: false,
+            cap_completed_runs: false,
+        },
+    },
+    AssistPreset {
+        name: "Purist",
+        assists: Assists {
+            auto_cross_blank_lines: false,
+            guard_completed_lines: false,
+            cap_completed_runs: false,
+        },
+    },
+];
+
+pub const MAX_HINTS: u8 = 5;
+
+/// Largest board side, so that every clue fits in a [`u8`].
+pub const MAX_DIMENSION: usize = u8::MAX as usize;
+
+impl GameConfig {
+    pub fn new(width: usize, height: usize, filled_count: usize) -> Result<Self, ConfigError> {
+        if width == 0 || width > MAX_DIMENSION {
+            Err(ConfigError::InvalidWidth)
+        } else if height == 0 || height > MAX_DIMENSION {
+            Err(ConfigError::InvalidHeight)
+        } else if filled_count > width * height {
+            Err(ConfigError::TooManyFilled)
         } else {
-            self.winstate = Winstate::InProgress
+            Ok(GameConfig {
+                width,
+                height,
+                filled_count,
+                relaxed: false,
+                hints: 3,
+                seed: None,
+                assists: Assists::default(),
+                forgiveness_seconds: 0,
+                no_loss: false,
+                no_guessing: false,
+            })
+        }
+    }
+}
+
+impl GameConfig {
+    /// Encodes the board size, seed and rules as `WxH-FILLED-SEED-hHINTS` followed by
+    /// one letter per enabled option: `r` for relaxed, `c` for auto-cross, `g` for
+    /// guarding completed lines, `s` for capping completed runs, `f` plus the seconds of
+    /// the forgiveness window, `n` for games that can't be lost, and `l` for boards
+    /// solvable by line logic.
+    /// The seed a game with this config is generated from, [`GameConfig::seed`] or a
+    /// random one when that is unset.
+    pub fn pick_seed(&self) -> u64 {
+        self.seed.unwrap_or_else(|| thread_rng().gen())
+    }
+
+    pub fn share_code(&self, seed: u64) -> String {
+        let mut code = format!(
+            "{}x{}-{}-{}-h{}",
+            self.width, self.height, self.filled_count, seed, self.hints
+        );
+        if self.relaxed {
+            code.push('r');
+        }
+        if self.assists.auto_cross_blank_lines {
+            code.push('c');
+        }
+        if self.assists.guard_completed_lines {
+            code.push('g');
+        }
+        if self.assists.cap_completed_runs {
+            code.push('s');
+        }
+        if self.forgiveness_seconds > 0 {
+            code.push_str(&format!("f{}", self.forgiveness_seconds));
+        }
+        if self.no_loss {
+            code.push('n');
+        }
+        if self.no_guessing {
+            code.push('l');
+        }
+        code
+    }
+    pub fn from_share_code(code: &str) -> Result<Self, ConfigError> {
+        let mut parts = code.trim().split('-');
+        let (Some(size), Some(filled_count), Some(seed), Some(rules), None) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            return Err(ConfigError::InvalidShareCode);
         };
+        let number = |part: &str| part.parse().map_err(|_| ConfigError::InvalidShareCode);
+        let (width, height) = size.split_once('x').ok_or(ConfigError::InvalidShareCode)?;
+        let mut config = GameConfig::new(number(width)?, number(height)?, number(filled_count)?)?;
+        config.seed = Some(seed.parse().map_err(|_| ConfigError::InvalidShareCode)?);
+
+        let rules = rules
+            .strip_prefix('h')
+            .ok_or(ConfigError::InvalidShareCode)?;
+        let (hints, flags) = rules.split_at(
+            rules
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rules.len()),
+        );
+        config.hints = hints
+            .parse::<u8>()
+            .map_err(|_| ConfigError::InvalidShareCode)?
+            .min(MAX_HINTS);
+        let mut flags = flags.chars().peekable();
+        while let Some(flag) = flags.next() {
+            match flag {
+                'r' => config.relaxed = true,
+                'c' => config.assists.auto_cross_blank_lines = true,
+                'g' => config.assists.guard_completed_lines = true,
+                's' => config.assists.cap_completed_runs = true,
+                'n' => config.no_loss = true,
+                'l' => config.no_guessing = true,
+                'f' => {
+                    let seconds: String =
+                        std::iter::from_fn(|| flags.next_if(char::is_ascii_digit)).collect();
+                    config.forgiveness_seconds = seconds
+                        .parse::<u8>()
+                        .map_err(|_| ConfigError::InvalidShareCode)?
+                        .min(MAX_FORGIVENESS_SECONDS);
+                }
+                _ => return Err(ConfigError::InvalidShareCode),
+            }
+        }
+        Ok(config)
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConfigError {
+    InvalidWidth,
+    InvalidHeight,
+    InvalidFilledCount,
+    TooManyFilled,
+    InvalidShareCode,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::InvalidWidth => {
+                write!(f, "Width must be a number from 1 to {MAX_DIMENSION}")
+            }
+            ConfigError::InvalidHeight => {
+                write!(f, "Height must be a number from 1 to {MAX_DIMENSION}")
+            }
+            ConfigError::InvalidFilledCount => f.write_str("Filled boxes must be a number"),
+            ConfigError::TooManyFilled => f.write_str("There are more filled boxes than tiles"),
+            ConfigError::InvalidShareCode => f.write_str("Not a seed or share code"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GridError {
+    Empty,
+    /// A character that isn't `#`, `1`, `.` or `0`, with its line number.
+    InvalidCharacter(char, usize),
+    /// A row whose length differs from the first one, by line number.
+    RaggedRow(usize),
+    TooLarge,
+}
+
+impl std::fmt::Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "The puzzle has no rows"),
+            GridError::InvalidCharacter(character, line) => {
+                write!(f, "Unexpected '{character}' on line {line}")
+            }
+            GridError::RaggedRow(line) => {
+                write!(f, "Line {line} is not as long as the first row")
+            }
+            GridError::TooLarge => {
+                write!(
+                    f,
+                    "Puzzles can be at most {MAX_DIMENSION} tiles wide and tall"
+                )
+            }
+        }
+    }
+}
+
+/// What generation could establish about a board's clues.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Solvability {
+    /// Row and column deductions alone solve the board.
+    LineLogic,
+    /// The clues have a single solution, which may take more than line logic to find.
+    Unique,
+    /// The clues may have several solutions: none of the boards tried could be proven
+    /// to have only one.
+    #[default]
+    Unproven,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum Winstate {
     Won,
     Lost,
     InProgress,
 }
 
-pub struct Menu {
-    pub width_input: String,
-    pub height_input: String,
-    pub start_pressed: bool,
-    pub filled_count_input: String,
-}
-
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Tile {
     pub hidden: bool,
     pub empty: bool,
     pub marked: bool,
 }
 
-#[derive(Clone)]
+/// Lengths of the runs of filled tiles in one row or column, in order.
+pub type Clues = SmallVec<[u8; 8]>;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Board {
     pub board_vec: Vec<Tile>,
     pub width: usize,
     pub height: usize,
     pub filled_count: usize,
-    pub vertical_count: Vec<Vec<usize>>,
-    pub horizontal_count: Vec<Vec<usize>>,
+    pub vertical_count: Vec<Clues>,
+    pub horizontal_count: Vec<Clues>,
+}
+
+// Printing every tile of a large board would drown out whatever else is printed.
+impl std::fmt::Debug for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Board")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("filled_count", &self.filled_count)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Board {
@@ -92,18 +811,19 @@ impl Board {
             horizontal_count: Vec::new(),
         }
     }
-    fn fill_boxes_randomly(&mut self, filled_count: usize) {
+    fn fill_boxes_randomly<R: Rng + ?Sized>(&mut self, filled_count: usize, rng: &mut R) {
         let mut ids: Vec<usize> = (0..self.width * self.height).collect();
-        rand::seq::SliceRandom::shuffle(ids.as_mut_slice(), &mut thread_rng());
+        rand::seq::SliceRandom::shuffle(ids.as_mut_slice(), rng);
         ids.iter()
             .take(filled_count)
             .for_each(|&id| self.board_vec[id].empty = false);
     }
+    #[cfg_attr(feature = "profiling", profiling::function)]
     fn count_vertical(&mut self) {
         self.vertical_count = (0..self.width)
             .map(|column| {
                 let mut consecutive = 0;
-                (0..self.height).fold(Vec::new(), |mut acc, row| {
+                (0..self.height).fold(Clues::new(), |mut acc, row| {
                     let tile_is_empty =
                         self.board_vec[pair_to_index(row, column, self.width)].empty;
                     if consecutive > 0 && tile_is_empty {
@@ -124,11 +844,12 @@ impl Board {
             })
             .collect();
     }
+    #[cfg_attr(feature = "profiling", profiling::function)]
     fn count_horizontal(&mut self) {
         self.horizontal_count = (0..self.height)
             .map(|row| {
                 let mut consecutive = 0;
-                (0..self.width).fold(Vec::new(), |mut acc, column| {
+                (0..self.width).fold(Clues::new(), |mut acc, column| {
                     let tile_is_empty =
                         self.board_vec[pair_to_index(row, column, self.width)].empty;
                     if consecutive > 0 && tile_is_empty {
@@ -149,12 +870,548 @@ impl Board {
             })
             .collect();
     }
-    pub fn new(width: usize, height: usize, filled_count: usize) -> Self {
+    /// Builds a board from rows of `#` (filled) and `.` (empty) characters.
+    pub fn from_rows(rows: &[&str]) -> Self {
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let cells: Vec<bool> = rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .map(|cell| cell == '#')
+            .collect();
+        Self::from_solution(width, rows.len(), &cells)
+    }
+    /// Parses a solution drawn with `#` or `1` for filled and `.` or `0` for empty
+    /// tiles, one row per line. Blank lines and whitespace inside rows are ignored.
+    pub fn parse_grid(input: &str) -> Result<Self, GridError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for (line_number, line) in input.lines().enumerate() {
+            let row: Vec<char> = line.chars().filter(|cell| !cell.is_whitespace()).collect();
+            if row.is_empty() {
+                continue;
+            }
+            if *width.get_or_insert(row.len()) != row.len() {
+                return Err(GridError::RaggedRow(line_number + 1));
+            }
+            for cell in row {
+                cells.push(match cell {
+                    '#' | '1' => true,
+                    '.' | '0' => false,
+                    other => return Err(GridError::InvalidCharacter(other, line_number + 1)),
+                });
+            }
+            height += 1;
+        }
+        let width = width.ok_or(GridError::Empty)?;
+        if width > MAX_DIMENSION || height > MAX_DIMENSION {
+            return Err(GridError::TooLarge);
+        }
+        Ok(Self::from_solution(width, height, &cells))
+    }
+    fn from_solution(width: usize, height: usize, cells: &[bool]) -> Self {
+        let mut board = Self::gen_empty(width, height);
+        cells
+            .iter()
+            .zip(board.board_vec.iter_mut())
+            .for_each(|(&filled, tile)| tile.empty = !filled);
+        board.filled_count = board.board_vec.iter().filter(|tile| !tile.empty).count();
+        board.count_vertical();
+        board.count_horizontal();
+        board
+    }
+    /// Ids of the tiles making up a line, in order.
+    pub fn line_ids(&self, line: Line) -> impl Iterator<Item = usize> {
+        let (start, step, length) = match line {
+            Line::Row(row) => (pair_to_index(row, 0, self.width), 1, self.width),
+            Line::Column(column) => (column, self.width, self.height),
+        };
+        (0..length).map(move |offset| start + offset * step)
+    }
+    /// The clues of a line.
+    pub fn clues(&self, line: Line) -> &Clues {
+        match line {
+            Line::Row(row) => &self.horizontal_count[row],
+            Line::Column(column) => &self.vertical_count[column],
+        }
+    }
+    /// What the player has settled of each tile: `Some(true)` for revealed filled tiles,
+    /// `Some(false)` for revealed empty ones and marks on empty tiles, `None` for the rest.
+    ///
+    /// Marks on filled tiles are left out, so deductions never build on a wrong mark.
+    pub fn known_cells(&self) -> Vec<Option<bool>> {
+        self.board_vec
+            .iter()
+            .map(|tile| {
+                if !tile.hidden {
+                    Some(!tile.empty)
+                } else if tile.marked && tile.empty {
+                    Some(false)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+    /// The first row or column where line logic settles a tile the player hasn't
+    /// revealed or rightly marked yet, going by what they have settled alone.
+    pub fn forced_line(&self) -> Option<Line> {
+        let deduction = solver::line_deduction(
+            &self.horizontal_count,
+            &self.vertical_count,
+            &self.known_cells(),
+        )?;
+        match deduction.reason {
+            solver::Reason::Line(line, _) => Some(line),
+            solver::Reason::Contradiction => None,
+        }
+    }
+    /// The next tile that follows from the clues and what the player has settled.
+    pub fn next_deduction(&self) -> Option<solver::Deduction> {
+        solver::next_deduction(
+            &self.horizontal_count,
+            &self.vertical_count,
+            &self.known_cells(),
+        )
+    }
+    /// The row and the column crossing at a tile.
+    pub fn lines_through(&self, id: usize) -> [Line; 2] {
+        [Line::Row(id / self.width), Line::Column(id % self.width)]
+    }
+    /// Share of the tiles in `line` that were revealed or marked, from 0 to 1.
+    pub fn line_progress(&self, line: Line) -> f32 {
+        let (resolved, length) = self.line_ids(line).fold((0, 0), |(resolved, length), id| {
+            let tile = self.board_vec[id];
+            (
+                resolved + usize::from(!tile.hidden || tile.marked),
+                length + 1,
+            )
+        });
+        if length == 0 {
+            1.0
+        } else {
+            resolved as f32 / length as f32
+        }
+    }
+    pub fn is_line_completed(&self, line: Line) -> bool {
+        self.line_ids(line).all(|id| {
+            let tile = self.board_vec[id];
+            tile.empty || !tile.hidden
+        })
+    }
+    /// Marks every tile of lines with no clues and reveals every tile of lines
+    /// whose single clue spans the whole line.
+    #[cfg_attr(feature = "profiling", profiling::function)]
+    pub fn resolve_trivial_lines(&mut self) {
+        let (width, height) = (self.width, self.height);
+        let rows = self
+            .horizontal_count
+            .iter()
+            .enumerate()
+            .flat_map(|(row, counts)| {
+                (0..width).map(move |column| (counts, pair_to_index(row, column, width), width))
+            });
+        let columns = self
+            .vertical_count
+            .iter()
+            .enumerate()
+            .flat_map(|(column, counts)| {
+                (0..height).map(move |row| (counts, pair_to_index(row, column, width), height))
+            });
+        let updates: Vec<(usize, bool)> = rows
+            .chain(columns)
+            .filter_map(|(counts, id, length)| match counts.as_slice() {
+                [] => Some((id, false)),
+                [count] if usize::from(*count) == length => Some((id, true)),
+                _ => None,
+            })
+            .collect();
+        updates.into_iter().for_each(|(id, filled)| {
+            if filled {
+                self.board_vec[id].hidden = false;
+            } else {
+                self.board_vec[id].marked = true;
+            }
+        });
+    }
+    pub fn clear_progress(&mut self) {
+        self.board_vec.iter_mut().for_each(|tile| {
+            tile.hidden = true;
+            tile.marked = false;
+        });
+    }
+    /// The tiles [`Board::for_config`] first scatters for `seed`, before it moves any to
+    /// leave the clues a single solution. Nothing is checked, so this is quick enough for
+    /// a sample of what a game with `config` looks like.
+    pub fn candidate_for_config(config: &GameConfig, seed: u64) -> Self {
+        Self::generate_candidate(
+            config.width,
+            config.height,
+            config.filled_count,
+            &mut ChaCha8Rng::seed_from_u64(seed),
+        )
+    }
+    /// The board a game with `config` is played on for `seed`.
+    ///
+    /// ChaCha8 is used rather than `StdRng`, whose output may change with any rand
+    /// release, so a seed or share code gives everyone the same board.
+    pub fn for_config(config: &GameConfig, seed: u64) -> (Self, Solvability) {
+        Self::generate(
+            config.width,
+            config.height,
+            config.filled_count,
+            &mut ChaCha8Rng::seed_from_u64(seed),
+            config.no_guessing,
+        )
+    }
+    /// Scatters the filled tiles and moves them about until the clues have a single
+    /// solution, so a correct logical solve always wins, or with `no_guessing` until line
+    /// logic alone solves them.
+    ///
+    /// Proving uniqueness can take the solver too long on large sparse boards, so after
+    /// [`GENERATION_ATTEMPTS`] boards the last one is kept and reported as
+    /// [`Solvability::Unproven`]. When none of [`NO_GUESSING_ATTEMPTS`] boards yields to
+    /// line logic, the first of them made to have a single solution is kept instead.
+    /// Boards of more than [`MAX_PROVEN_TILES`] tiles are kept as first scattered and
+    /// reported as unproven straight away.
+    #[cfg_attr(feature = "profiling", profiling::function)]
+    fn generate<R: Rng + ?Sized>(
+        width: usize,
+        height: usize,
+        filled_count: usize,
+        rng: &mut R,
+        no_guessing: bool,
+    ) -> (Self, Solvability) {
+        let mut board = Self::generate_candidate(width, height, filled_count, rng);
+        if width * height > MAX_PROVEN_TILES {
+            return (board, Solvability::Unproven);
+        }
+        if no_guessing {
+            let mut unique = None;
+            for attempt in 0..NO_GUESSING_ATTEMPTS {
+                if attempt > 0 {
+                    board = Self::generate_candidate(width, height, filled_count, rng);
+                }
+                if board.is_line_solvable() {
+                    return (board, Solvability::LineLogic);
+                }
+                // Proving a fallback unique costs as much as a board without the option.
+                if unique.is_none() && attempt < GENERATION_ATTEMPTS && board.make_unique(rng) {
+                    // The moves may have let line logic through.
+                    if board.is_line_solvable() {
+                        return (board, Solvability::LineLogic);
+                    }
+                    unique = Some(board.clone());
+                }
+            }
+            return match unique {
+                Some(unique) => (unique, Solvability::Unique),
+                None => (board, Solvability::Unproven),
+            };
+        }
+        for attempt in 0..GENERATION_ATTEMPTS {
+            if attempt > 0 {
+                board = Self::generate_candidate(width, height, filled_count, rng);
+            }
+            if board.make_unique(rng) {
+                return (board, Solvability::Unique);
+            }
+        }
+        (board, Solvability::Unproven)
+    }
+    /// Moves filled tiles where the clues leave a choice until they have a single
+    /// solution, giving up after [`UNIQUENESS_MOVES`] moves or when the solver can't
+    /// tell. Returns whether the clues ended up with a single solution.
+    fn make_unique<R: Rng + ?Sized>(&mut self, rng: &mut R) -> bool {
+        let mut moves = 0;
+        loop {
+            match self.solutions() {
+                Solutions::Unique(_) => return true,
+                Solutions::Multiple(first, second) if moves < UNIQUENESS_MOVES => {
+                    self.break_tie(&first, &second, rng);
+                    moves += 1;
+                }
+                _ => return false,
+            }
+        }
+    }
+    /// Swaps a tile where `first` and `second`, two solutions of the clues, disagree with
+    /// a tile of the other kind, preferably a neighbouring one. The clues change there,
+    /// while the number of filled tiles stays the same.
+    fn break_tie<R: Rng + ?Sized>(&mut self, first: &[bool], second: &[bool], rng: &mut R) {
+        let Some(id) = (0..first.len())
+            .filter(|&id| first[id] != second[id])
+            .choose(rng)
+        else {
+            return;
+        };
+        let empty = self.board_vec[id].empty;
+        let (row, column) = (id / self.width, id % self.width);
+        let neighbours = [
+            row.checked_sub(1).map(|row| (row, column)),
+            (row + 1 < self.height).then_some((row + 1, column)),
+            column.checked_sub(1).map(|column| (row, column)),
+            (column + 1 < self.width).then_some((row, column + 1)),
+        ];
+        let swap_with = neighbours
+            .into_iter()
+            .flatten()
+            .map(|(row, column)| pair_to_index(row, column, self.width))
+            .filter(|&other| self.board_vec[other].empty != empty)
+            .choose(rng)
+            .or_else(|| {
+                (0..self.board_vec.len())
+                    .filter(|&other| self.board_vec[other].empty != empty)
+                    .choose(rng)
+            });
+        if let Some(other) = swap_with {
+            self.board_vec[id].empty = !empty;
+            self.board_vec[other].empty = empty;
+            self.count_vertical();
+            self.count_horizontal();
+        }
+    }
+    fn generate_candidate<R: Rng + ?Sized>(
+        width: usize,
+        height: usize,
+        filled_count: usize,
+        rng: &mut R,
+    ) -> Self {
         let mut board = Self::gen_empty(width, height);
-        board.fill_boxes_randomly(filled_count);
+        board.fill_boxes_randomly(filled_count, rng);
         board.filled_count = filled_count;
         board.count_vertical();
         board.count_horizontal();
         board
     }
+    /// Every board matching this board's clues, as far as [`solver::solve`] can tell.
+    pub fn solutions(&self) -> Solutions {
+        solver::solve(&self.horizontal_count, &self.vertical_count)
+    }
+    /// Whether this board is the only one matching its clues.
+    pub fn has_unique_solution(&self) -> bool {
+        matches!(self.solutions(), Solutions::Unique(_))
+    }
+    /// Whether repeatedly applying row and column deductions solves this board from its
+    /// clues alone, without ever guessing.
+    pub fn is_line_solvable(&self) -> bool {
+        solver::is_line_solvable(&self.horizontal_count, &self.vertical_count)
+    }
+}
+
+/// Most tiles a board may have for generation to look for a single solution. Past 50×50
+/// a search can take the solver many seconds, and sparse boards rarely end up proven.
+const MAX_PROVEN_TILES: usize = 50 * 50;
+
+/// How many random boards are tried in search of one with a unique solution.
+const GENERATION_ATTEMPTS: usize = 10;
+
+/// How many tiles are moved on one board to leave its clues a single solution before
+/// another board is tried.
+const UNIQUENESS_MOVES: usize = 20;
+
+/// How many random boards are tried in search of one line logic solves. Checking one
+/// takes no guessing, so many more can be tried.
+const NO_GUESSING_ATTEMPTS: usize = 100;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn share_code_round_trips() {
+        let mut config = GameConfig::new(12, 7, 30).unwrap();
+        config.relaxed = true;
+        config.hints = 2;
+        config.forgiveness_seconds = 3;
+        config.no_loss = true;
+        config.no_guessing = true;
+        config.assists = Assists {
+            auto_cross_blank_lines: true,
+            guard_completed_lines: true,
+            cap_completed_runs: true,
+        };
+        let code = config.share_code(u64::MAX);
+        assert_eq!(code, "12x7-30-18446744073709551615-h2rcgsf3nl");
+        let parsed = GameConfig::from_share_code(&code).unwrap();
+        assert_eq!(parsed.seed, Some(u64::MAX));
+        assert_eq!(parsed.share_code(u64::MAX), code);
+
+        let plain = GameConfig::new(10, 10, 65).unwrap();
+        let parsed = GameConfig::from_share_code(&plain.share_code(7)).unwrap();
+        assert_eq!(parsed.share_code(7), "10x10-65-7-h3");
+        assert_eq!(parsed.assists, Assists::default());
+        assert!(!parsed.relaxed && !parsed.no_loss && !parsed.no_guessing);
+    }
+
+    #[test]
+    fn malformed_share_codes_are_rejected() {
+        for code in [
+            "",
+            "10x10-65-7",
+            "10x10-65-7-h0-r",
+            "10-65-7-h0",
+            "10xten-65-7-h0",
+            "10x10-65-seven-h0",
+            "10x10-65-7-0",
+            "10x10-65-7-h",
+            "10x10-65-7-h0q",
+            "10x10-65-7-h0f",
+        ] {
+            assert_eq!(
+                GameConfig::from_share_code(code).unwrap_err(),
+                ConfigError::InvalidShareCode,
+                "{code:?}"
+            );
+        }
+        assert_eq!(
+            GameConfig::from_share_code("0x10-0-7-h0").unwrap_err(),
+            ConfigError::InvalidWidth
+        );
+        assert_eq!(
+            GameConfig::from_share_code("10x10-101-7-h0").unwrap_err(),
+            ConfigError::TooManyFilled
+        );
+    }
+
+    #[test]
+    fn grids_parse_into_their_clues() {
+        let board = Board::parse_grid("#.#\n 1 1 0 \n\n...\n").unwrap();
+        assert_eq!((board.width, board.height, board.filled_count), (3, 3, 4));
+        assert_eq!(board.horizontal_count[0].as_slice(), [1, 1]);
+        assert_eq!(board.horizontal_count[1].as_slice(), [2]);
+        assert!(board.horizontal_count[2].is_empty());
+        assert_eq!(board.vertical_count[0].as_slice(), [2]);
+    }
+
+    #[test]
+    fn malformed_grids_are_rejected() {
+        assert_eq!(Board::parse_grid(" \n\n").err(), Some(GridError::Empty));
+        assert_eq!(
+            Board::parse_grid("#.\n#x").err(),
+            Some(GridError::InvalidCharacter('x', 2))
+        );
+        assert_eq!(
+            Board::parse_grid("#.\n#..").err(),
+            Some(GridError::RaggedRow(2))
+        );
+        let too_wide = "#".repeat(MAX_DIMENSION + 1);
+        assert_eq!(
+            Board::parse_grid(&too_wide).err(),
+            Some(GridError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn generated_boards_have_unique_solutions() {
+        let config = GameConfig::new(10, 10, 60).unwrap();
+        for seed in 0..10 {
+            let (board, solvability) = Board::for_config(&config, seed);
+            assert_eq!(solvability, Solvability::Unique, "seed {seed}");
+            assert!(board.has_unique_solution(), "seed {seed}");
+            assert_eq!(board.filled_count, 60);
+        }
+        let mut config = config;
+        config.no_guessing = true;
+        for seed in 0..10 {
+            let (board, solvability) = Board::for_config(&config, seed);
+            assert_eq!(solvability, Solvability::LineLogic, "seed {seed}");
+            assert!(board.is_line_solvable(), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn candidates_are_where_generation_starts() {
+        let config = GameConfig::new(51, 50, 1275).unwrap();
+        let candidate = Board::candidate_for_config(&config, 3);
+        let (board, _) = Board::for_config(&config, 3);
+        assert_eq!(candidate.horizontal_count, board.horizontal_count);
+        assert_eq!(candidate.vertical_count, board.vertical_count);
+    }
+
+    #[test]
+    fn oversized_boards_skip_the_search() {
+        let config = GameConfig::new(51, 50, 1275).unwrap();
+        let (board, solvability) = Board::for_config(&config, 7);
+        assert_eq!(solvability, Solvability::Unproven);
+        assert_eq!(board.filled_count, 1275);
+    }
+
+    #[test]
+    fn seeds_regenerate_the_same_board() {
+        let config = GameConfig::new(15, 15, 135).unwrap();
+        let (first, _) = Board::for_config(&config, 42);
+        let (second, _) = Board::for_config(&config, 42);
+        assert_eq!(first.horizontal_count, second.horizontal_count);
+        assert_eq!(first.vertical_count, second.vertical_count);
+    }
+
+    #[test]
+    fn paused_games_stand_still() {
+        let config = GameConfig::new(3, 1, 2).unwrap();
+        let mut game = Game::with_board(config, Board::from_rows(&["##."]));
+        let idle_from = Instant::now();
+        std::thread::sleep(Duration::from_millis(50));
+        game.pause_since(idle_from);
+        let elapsed = game.clock.elapsed();
+        assert!(elapsed < Duration::from_millis(50));
+        assert!(game.reveal(0).is_empty() && game.hint().is_empty());
+        assert!(game.board.board_vec[0].hidden);
+        // Nothing a move would do may start the clock again.
+        game.wincheck();
+        assert_eq!(game.clock.elapsed(), elapsed);
+        game.resume();
+        assert!(!game.reveal(0).is_empty());
+    }
+
+    #[test]
+    fn saved_games_come_back_as_they_were_left() {
+        let mut config = GameConfig::new(3, 3, 3).unwrap();
+        config.relaxed = true;
+        let mut game = Game::with_board(config, Board::from_rows(&["##.", "#..", "..."]));
+        game.seed = u64::MAX;
+        game.reveal(0);
+        game.toggle_mark(8);
+        let saved = toml::to_string(&game).unwrap();
+        let mut restored: Game = toml::from_str(&saved).unwrap();
+        assert!(restored.integrity_problems().is_empty());
+        assert!(restored.board.board_vec == game.board.board_vec);
+        assert_eq!(restored.seed, u64::MAX);
+        assert!(matches!(restored.winstate, Winstate::InProgress));
+        // The moves made before saving can still be taken back.
+        restored.undo();
+        restored.undo();
+        assert!(restored.board.board_vec[0].hidden && !restored.board.board_vec[8].marked);
+    }
+
+    #[test]
+    fn hints_ignore_wrong_marks() {
+        let mut config = GameConfig::new(3, 1, 2).unwrap();
+        config.hints = 5;
+        let mut game = Game::with_board(config, Board::from_rows(&["##."]));
+        game.toggle_mark(0);
+        for _ in 0..3 {
+            let events = game.hint();
+            assert!(
+                !events
+                    .iter()
+                    .any(|event| matches!(event, GameEvent::MistakeMade(_))),
+                "{events:?}"
+            );
+        }
+        assert!(matches!(game.winstate, Winstate::Won));
+    }
+
+    #[test]
+    fn hints_past_the_budget_cost_time() {
+        let mut config = GameConfig::new(3, 1, 2).unwrap();
+        config.hints = 1;
+        let mut game = Game::with_board(config, Board::from_rows(&["##."]));
+        game.charge_hint();
+        assert_eq!((game.hints_left(), game.hints_used), (0, 1));
+        assert!(game.clock.elapsed() < HINT_PENALTY);
+        game.charge_hint();
+        assert_eq!(game.hints_used, 2);
+        assert!(game.clock.elapsed() >= HINT_PENALTY);
+    }
 }
@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// A moment of a solve the player marked, with what they noted about it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Lap {
+    /// Time on the clock when the lap was taken.
+    pub at: Duration,
+    pub label: String,
+}
+
+/// Time spent on a game, standing still while the game is over.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(from = "SavedClock", into = "SavedClock")]
+pub struct Clock {
+    /// Time played up to when the clock was last stopped.
+    banked: Duration,
+    /// When the clock was last started, `None` while it stands still.
+    running_since: Option<Instant>,
+}
+
+/// How a [`Clock`] is saved: the time played so far, whether or not it is running. A
+/// restored clock stands still until it is started again.
+#[derive(Serialize, Deserialize)]
+struct SavedClock {
+    banked: Duration,
+}
+
+impl From<Clock> for SavedClock {
+    fn from(clock: Clock) -> Self {
+        SavedClock {
+            banked: clock.elapsed(),
+        }
+    }
+}
+
+impl From<SavedClock> for Clock {
+    fn from(saved: SavedClock) -> Self {
+        Clock {
+            banked: saved.banked,
+            running_since: None,
+        }
+    }
+}
+
+impl Clock {
+    pub fn started() -> Self {
+        Clock {
+            banked: Duration::ZERO,
+            running_since: Some(Instant::now()),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.banked
+            + self
+                .running_since
+                .map_or(Duration::ZERO, |since| since.elapsed())
+    }
+
+    /// Puts `penalty` on the clock, as if that much more time had been played.
+    pub fn add(&mut self, penalty: Duration) {
+        self.banked += penalty;
+    }
+
+    /// Stops the clock as if that had happened at `at`, taking any time since back off it.
+    pub fn stop_at(&mut self, at: Instant) {
+        if let Some(since) = self.running_since.take() {
+            self.banked += at.saturating_duration_since(since);
+        }
+    }
+
+    /// Starts or stops the clock, keeping the time played so far either way.
+    pub fn set_running(&mut self, running: bool) {
+        match (running, self.running_since) {
+            (true, None) => self.running_since = Some(Instant::now()),
+            (false, Some(since)) => {
+                self.banked += since.elapsed();
+                self.running_since = None;
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// A row or column of the board.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Line {
+    Row(usize),
+    Column(usize),
+}
+
+/// Something that happened in a game, reported by the [`Game`](super::Game) methods
+/// that change the board so other parts of the app can react to it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GameEvent {
+    /// A filled tile was revealed.
+    TileRevealed(usize),
+    /// A reveal was refused by the completed line guard.
+    RevealBlocked(usize),
+    /// An empty tile was revealed.
+    MistakeMade(usize),
+    /// An empty tile was clicked within the forgiveness window, so it was marked instead.
+    MistakeForgiven(usize),
+    TileMarked {
+        id: usize,
+        marked: bool,
+    },
+    /// A hint is about to reveal or mark this tile.
+    HintUsed(usize),
+    /// Nothing follows from the tiles the player has settled, so no hint was spent.
+    HintUnavailable,
+    /// Every filled tile of the line is now revealed.
+    LineCompleted(Line),
+    GameWon,
+    GameLost,
+}
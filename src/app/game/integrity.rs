@@ -0,0 +1,80 @@
+use super::judge::{Judge, LossRule};
+use super::{Board, Game, Winstate};
+
+impl Game {
+    /// Everything wrong with the state of this game, described for a developer.
+    ///
+    /// The board must agree with its own clues, no filled tile may be both revealed and
+    /// marked as empty, the win state must follow from the tiles and the undo history
+    /// must lead up to them.
+    pub fn integrity_problems(&self) -> Vec<String> {
+        let board = &self.board;
+        let mut problems = Vec::new();
+        if board.board_vec.len() != board.width * board.height {
+            problems.push(format!(
+                "{} tiles on a {}x{} board",
+                board.board_vec.len(),
+                board.width,
+                board.height
+            ));
+            // Nothing else can be checked without reading out of bounds.
+            return problems;
+        }
+        let filled = board.board_vec.iter().filter(|tile| !tile.empty).count();
+        if filled != board.filled_count {
+            problems.push(format!(
+                "{filled} filled tiles, but filled_count is {}",
+                board.filled_count
+            ));
+        }
+        let cells: Vec<bool> = board.board_vec.iter().map(|tile| !tile.empty).collect();
+        let expected = Board::from_solution(board.width, board.height, &cells);
+        if expected.horizontal_count != board.horizontal_count {
+            problems.push(format!(
+                "row clues {:?} don't match the tiles, which give {:?}",
+                board.horizontal_count, expected.horizontal_count
+            ));
+        }
+        if expected.vertical_count != board.vertical_count {
+            problems.push(format!(
+                "column clues {:?} don't match the tiles, which give {:?}",
+                board.vertical_count, expected.vertical_count
+            ));
+        }
+        for (id, tile) in board.board_vec.iter().enumerate() {
+            if !tile.empty && !tile.hidden && tile.marked {
+                problems.push(format!("filled tile {id} is both revealed and marked"));
+            }
+        }
+        if !self.history.leads_to(&board.board_vec) {
+            problems.push(String::from("the undo history doesn't lead to the tiles"));
+        }
+        let mistakes = board
+            .board_vec
+            .iter()
+            .any(|tile| tile.empty && !tile.hidden);
+        let rules = Judge::new(&self.config);
+        match self.winstate {
+            Winstate::InProgress => {
+                if !matches!(rules.judge(board), Winstate::InProgress) {
+                    problems.push(String::from(
+                        "the game is still in progress but already over",
+                    ));
+                }
+            }
+            Winstate::Won => {
+                if board.board_vec.iter().any(|tile| tile.hidden) {
+                    problems.push(String::from("the game is won but tiles are still hidden"));
+                }
+            }
+            Winstate::Lost => {
+                if rules.loss == LossRule::Never {
+                    problems.push(String::from("a game that can't be lost was lost"));
+                } else if !mistakes {
+                    problems.push(String::from("the game is lost without a mistake"));
+                }
+            }
+        }
+        problems
+    }
+}
@@ -0,0 +1,55 @@
+use super::{Board, GameConfig, Winstate};
+
+/// What ends a game in a loss.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LossRule {
+    /// Revealing any empty tile loses the game on the spot.
+    #[default]
+    InstantLoss,
+    /// Mistakes stay on the board but the game goes on until it is won.
+    Never,
+}
+
+/// Decides whether a board is won, lost or still in play under the rules of a game.
+///
+/// Marks never count either way, so a line crossed out before the game started (or by
+/// the auto-cross assist) can't win or lose a game on its own.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Judge {
+    pub loss: LossRule,
+}
+
+impl Judge {
+    pub fn new(config: &GameConfig) -> Self {
+        Judge {
+            loss: if config.no_loss {
+                LossRule::Never
+            } else {
+                LossRule::InstantLoss
+            },
+        }
+    }
+
+    pub fn judge(&self, board: &Board) -> Winstate {
+        let mistakes = board
+            .board_vec
+            .iter()
+            .filter(|tile| tile.empty && !tile.hidden)
+            .count();
+        let lost = match self.loss {
+            LossRule::InstantLoss => mistakes > 0,
+            LossRule::Never => false,
+        };
+        if lost {
+            Winstate::Lost
+        } else if board
+            .board_vec
+            .iter()
+            .all(|tile| tile.empty || !tile.hidden)
+        {
+            Winstate::Won
+        } else {
+            Winstate::InProgress
+        }
+    }
+}
@@ -0,0 +1,504 @@
+use super::{pair_to_index, Clues, Line};
+
+/// What solving a set of clues found.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Solutions {
+    /// No board matches the clues.
+    None,
+    /// Exactly one board does, given as whether each tile is filled, row by row.
+    Unique(Vec<bool>),
+    /// Several boards do; these are the first two found.
+    Multiple(Vec<bool>, Vec<bool>),
+    /// The search ran out of guesses before it could tell.
+    Undecided,
+}
+
+/// A tile the clues and the tiles known so far settle.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Deduction {
+    pub id: usize,
+    pub filled: bool,
+    pub reason: Reason,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Reason {
+    /// The clues of this line leave no other choice.
+    Line(Line, LineStep),
+    /// The other choice contradicts the clues once line logic follows it through.
+    Contradiction,
+}
+
+/// How a single line settles a cell, with positions counted along the line.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LineStep {
+    /// However this run is placed, it covers the cells from `first` to `last`.
+    Overlap { run: u8, first: usize, last: usize },
+    /// No run can reach the cell.
+    Unreachable,
+    /// Every arrangement of the clues agrees on the cell, for reasons less simple.
+    Exhaustive,
+}
+
+/// How many guesses [`solve`] may make before giving up.
+const SEARCH_BUDGET: usize = 200;
+
+/// Finds the boards whose rows have the `rows` clues and whose columns have the
+/// `columns` clues, by line logic and, where that stalls, guessing.
+pub fn solve(rows: &[Clues], columns: &[Clues]) -> Solutions {
+    solve_within(rows, columns, SEARCH_BUDGET)
+}
+
+/// [`solve`] allowed `budget` guesses.
+fn solve_within(rows: &[Clues], columns: &[Clues], budget: usize) -> Solutions {
+    let (width, height) = (columns.len(), rows.len());
+    let mut search = Search {
+        width,
+        height,
+        rows,
+        columns,
+        limit: 2,
+        found: Vec::new(),
+        budget,
+    };
+    if !search.explore(vec![None; width * height], all_lines(width, height)) {
+        return Solutions::Undecided;
+    }
+    let mut found = search.found.into_iter();
+    match (found.next(), found.next()) {
+        (None, _) => Solutions::None,
+        (Some(only), None) => Solutions::Unique(only),
+        (Some(first), Some(second)) => Solutions::Multiple(first, second),
+    }
+}
+
+/// The first tile a single row or column settles, given the `known` tiles as in
+/// [`solve_line`]. Lines the known tiles contradict are skipped.
+pub fn line_deduction(
+    rows: &[Clues],
+    columns: &[Clues],
+    known: &[Option<bool>],
+) -> Option<Deduction> {
+    let width = columns.len();
+    all_lines(width, rows.len()).into_iter().find_map(|line| {
+        let (clues, ids) = line_cells(line, rows, columns, width);
+        let cells: Vec<Option<bool>> = ids.iter().map(|&id| known[id]).collect();
+        let solved = solve_line(clues, &cells)?;
+        let position = solved
+            .iter()
+            .zip(&cells)
+            .position(|(solved, known)| solved.is_some() && known.is_none())?;
+        let filled = solved[position]?;
+        Some(Deduction {
+            id: ids[position],
+            filled,
+            reason: Reason::Line(line, explain_line(clues, &cells, position, filled)),
+        })
+    })
+}
+
+/// The next tile that follows from the clues and the `known` tiles: by a single line
+/// if possible, otherwise by trying both choices for a tile and finding one of them
+/// impossible. Returns `None` when neither works or the known tiles already contradict
+/// the clues.
+pub fn next_deduction(
+    rows: &[Clues],
+    columns: &[Clues],
+    known: &[Option<bool>],
+) -> Option<Deduction> {
+    if let Some(deduction) = line_deduction(rows, columns, known) {
+        return Some(deduction);
+    }
+    let (width, height) = (columns.len(), rows.len());
+    let search = Search {
+        width,
+        height,
+        rows,
+        columns,
+        limit: 1,
+        found: Vec::new(),
+        budget: 0,
+    };
+    if !search.propagate(&mut known.to_vec(), all_lines(width, height)) {
+        return None;
+    }
+    (0..known.len())
+        .filter(|&id| known[id].is_none())
+        .find_map(|id| {
+            [true, false].into_iter().find_map(|guess| {
+                let mut cells = known.to_vec();
+                cells[id] = Some(guess);
+                let crossing = vec![Line::Row(id / width), Line::Column(id % width)];
+                (!search.propagate(&mut cells, crossing)).then_some(Deduction {
+                    id,
+                    filled: !guess,
+                    reason: Reason::Contradiction,
+                })
+            })
+        })
+}
+
+/// Whether repeatedly applying row and column deductions settles every tile of the
+/// board with these clues, without ever guessing.
+pub fn is_line_solvable(rows: &[Clues], columns: &[Clues]) -> bool {
+    let (width, height) = (columns.len(), rows.len());
+    let search = Search {
+        width,
+        height,
+        rows,
+        columns,
+        limit: 1,
+        found: Vec::new(),
+        budget: 0,
+    };
+    let mut cells = vec![None; width * height];
+    search.propagate(&mut cells, all_lines(width, height)) && cells.iter().all(Option::is_some)
+}
+
+/// A depth-first search over the cells line logic alone can't settle.
+struct Search<'a> {
+    width: usize,
+    height: usize,
+    rows: &'a [Clues],
+    columns: &'a [Clues],
+    limit: usize,
+    /// Solutions found so far, as in [`Solutions::Unique`].
+    found: Vec<Vec<bool>>,
+    budget: usize,
+}
+
+impl Search<'_> {
+    /// Collects the solutions reachable from `cells` once the `pending` lines are
+    /// rechecked, returning `false` once out of budget.
+    fn explore(&mut self, mut cells: Vec<Option<bool>>, pending: Vec<Line>) -> bool {
+        if !self.propagate(&mut cells, pending) {
+            return true;
+        }
+        let Some(unknown) = cells.iter().position(Option::is_none) else {
+            self.found.push(cells.into_iter().flatten().collect());
+            return true;
+        };
+        for guess in [true, false] {
+            if self.found.len() >= self.limit {
+                break;
+            }
+            let Some(budget) = self.budget.checked_sub(1) else {
+                return false;
+            };
+            self.budget = budget;
+            let mut guessed = cells.clone();
+            guessed[unknown] = Some(guess);
+            let crossing = vec![
+                Line::Row(unknown / self.width),
+                Line::Column(unknown % self.width),
+            ];
+            if !self.explore(guessed, crossing) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Applies line logic to the `pending` lines and every line they change until
+    /// nothing changes, returning `false` on a contradiction.
+    fn propagate(&self, cells: &mut [Option<bool>], mut pending: Vec<Line>) -> bool {
+        while let Some(line) = pending.pop() {
+            let (clues, ids) = line_cells(line, self.rows, self.columns, self.width);
+            let known: Vec<Option<bool>> = ids.iter().map(|&id| cells[id]).collect();
+            let Some(solved) = solve_line(clues, &known) else {
+                return false;
+            };
+            for (&id, (solved, known)) in ids.iter().zip(solved.into_iter().zip(known)) {
+                if solved.is_some() && known.is_none() {
+                    cells[id] = solved;
+                    let crossing = match line {
+                        Line::Row(_) => Line::Column(id % self.width),
+                        Line::Column(_) => Line::Row(id / self.width),
+                    };
+                    if !pending.contains(&crossing) {
+                        pending.push(crossing);
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Settles every cell of a line that is the same in all arrangements of `clues`
+/// agreeing with the `known` cells, or returns `None` if no arrangement agrees.
+pub fn solve_line(clues: &[u8], known: &[Option<bool>]) -> Option<Vec<Option<bool>>> {
+    let layout = LineLayout::new(clues, known);
+    if !layout.fits[0][0] {
+        return None;
+    }
+    let length = known.len();
+    // Walks every arrangement forward, noting what each cell can be in at least one.
+    let mut can_fill = vec![false; length];
+    let mut can_empty = vec![false; length];
+    let mut reached = vec![vec![false; length + 1]; clues.len() + 1];
+    reached[0][0] = true;
+    for index in 0..length {
+        for clue in 0..=clues.len() {
+            if !reached[clue][index] {
+                continue;
+            }
+            if layout.may_skip(clue, index) {
+                can_empty[index] = true;
+                reached[clue][index + 1] = true;
+            }
+            if !layout.may_place(clue, index) {
+                continue;
+            }
+            let end = index + usize::from(clues[clue]);
+            can_fill[index..end].fill(true);
+            if end == length {
+                reached[clue + 1][length] = true;
+            } else {
+                can_empty[end] = true;
+                reached[clue + 1][end + 1] = true;
+            }
+        }
+    }
+    Some(
+        can_fill
+            .into_iter()
+            .zip(can_empty)
+            .map(|(fill, empty)| (fill != empty).then_some(fill))
+            .collect(),
+    )
+}
+
+/// Why line logic settles the cell at `position` of a line as `filled`, which it must.
+pub fn explain_line(
+    clues: &[u8],
+    known: &[Option<bool>],
+    position: usize,
+    filled: bool,
+) -> LineStep {
+    let length = known.len();
+    let leftmost = LineLayout::new(clues, known).leftmost();
+    let reversed_clues: Vec<u8> = clues.iter().rev().copied().collect();
+    let reversed_known: Vec<Option<bool>> = known.iter().rev().copied().collect();
+    let reversed = LineLayout::new(&reversed_clues, &reversed_known).leftmost();
+    let rightmost = clues
+        .iter()
+        .enumerate()
+        .map(|(clue, &run)| length - reversed[clues.len() - 1 - clue] - usize::from(run));
+    let mut spans = leftmost
+        .into_iter()
+        .zip(rightmost)
+        .zip(clues)
+        .map(|((left, right), &run)| (run, left, right));
+    if filled {
+        spans
+            .find_map(|(run, left, right)| {
+                let last = left + usize::from(run) - 1;
+                (right..=last)
+                    .contains(&position)
+                    .then_some(LineStep::Overlap {
+                        run,
+                        first: right,
+                        last,
+                    })
+            })
+            .unwrap_or(LineStep::Exhaustive)
+    } else if spans.all(|(run, left, right)| !(left..right + usize::from(run)).contains(&position))
+    {
+        LineStep::Unreachable
+    } else {
+        LineStep::Exhaustive
+    }
+}
+
+/// Which arrangements of a line's clues still agree with its known cells.
+struct LineLayout<'a> {
+    clues: &'a [u8],
+    known: &'a [Option<bool>],
+    /// `fits[clue][index]`: the clues from `clue` on can be laid out in the cells from
+    /// `index` on.
+    fits: Vec<Vec<bool>>,
+}
+
+impl<'a> LineLayout<'a> {
+    fn new(clues: &'a [u8], known: &'a [Option<bool>]) -> Self {
+        let length = known.len();
+        let mut layout = LineLayout {
+            clues,
+            known,
+            fits: vec![vec![false; length + 1]; clues.len() + 1],
+        };
+        for index in (0..=length).rev() {
+            layout.fits[clues.len()][index] =
+                (index..length).all(|index| layout.may_be_empty(index));
+        }
+        for clue in (0..clues.len()).rev() {
+            for index in (0..length).rev() {
+                layout.fits[clue][index] =
+                    layout.may_skip(clue, index) || layout.may_place(clue, index);
+            }
+        }
+        layout
+    }
+
+    fn may_be_empty(&self, index: usize) -> bool {
+        self.known[index] != Some(true)
+    }
+
+    /// Whether the cell at `index` can stay empty before the clues from `clue` on.
+    fn may_skip(&self, clue: usize, index: usize) -> bool {
+        self.may_be_empty(index) && self.fits[clue][index + 1]
+    }
+
+    /// Whether the run of `clue` can start at `index`, with the rest still fitting after it.
+    fn may_place(&self, clue: usize, index: usize) -> bool {
+        let Some(&run) = self.clues.get(clue) else {
+            return false;
+        };
+        let length = self.known.len();
+        let end = index + usize::from(run);
+        end <= length
+            && self.known[index..end]
+                .iter()
+                .all(|&cell| cell != Some(false))
+            && if end == length {
+                self.fits[clue + 1][length]
+            } else {
+                self.may_be_empty(end) && self.fits[clue + 1][end + 1]
+            }
+    }
+
+    /// Where each run starts when every run is pushed as far to the start as it goes.
+    /// The line must have an arrangement at all.
+    fn leftmost(&self) -> Vec<usize> {
+        let mut index = 0;
+        (0..self.clues.len())
+            .map(|clue| {
+                // Cells that can't start the run can always be skipped, or no
+                // arrangement would fit from `index` on.
+                let start = (index..)
+                    .find(|&start| self.may_place(clue, start))
+                    .unwrap_or(index);
+                index = start + usize::from(self.clues[clue]) + 1;
+                start
+            })
+            .collect()
+    }
+}
+
+/// Every row, then every column of a board.
+fn all_lines(width: usize, height: usize) -> Vec<Line> {
+    (0..height)
+        .map(Line::Row)
+        .chain((0..width).map(Line::Column))
+        .collect()
+}
+
+/// The clues of a line and the ids of its tiles, in order.
+fn line_cells<'a>(
+    line: Line,
+    rows: &'a [Clues],
+    columns: &'a [Clues],
+    width: usize,
+) -> (&'a Clues, Vec<usize>) {
+    match line {
+        Line::Row(row) => (
+            &rows[row],
+            (0..width)
+                .map(|column| pair_to_index(row, column, width))
+                .collect(),
+        ),
+        Line::Column(column) => (
+            &columns[column],
+            (0..rows.len())
+                .map(|row| pair_to_index(row, column, width))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::game::Board;
+
+    fn solutions(rows: &[&str]) -> Solutions {
+        let board = Board::from_rows(rows);
+        solve(&board.horizontal_count, &board.vertical_count)
+    }
+
+    fn cells(rows: &[&str]) -> Vec<bool> {
+        rows.iter()
+            .flat_map(|row| row.chars())
+            .map(|cell| cell == '#')
+            .collect()
+    }
+
+    #[test]
+    fn unique_puzzle_is_solved() {
+        let rows = ["####.", ".....", "#.#.#"];
+        assert_eq!(solutions(&rows), Solutions::Unique(cells(&rows)));
+        // Line logic stalls on this one, so the search has to guess.
+        let rows = [".##.", "#..#", "....", "...."];
+        assert_eq!(solutions(&rows), Solutions::Unique(cells(&rows)));
+    }
+
+    #[test]
+    fn ambiguous_puzzle_has_two_solutions() {
+        let Solutions::Multiple(first, second) = solutions(&["#.", ".#"]) else {
+            panic!("the diagonals share their clues");
+        };
+        let mut found = [first, second];
+        found.sort();
+        assert_eq!(found, [cells(&[".#", "#."]), cells(&["#.", ".#"])]);
+    }
+
+    #[test]
+    fn contradicting_clues_have_no_solution() {
+        let rows = [Clues::from_iter([1])];
+        let columns = [Clues::new()];
+        assert_eq!(solve(&rows, &columns), Solutions::None);
+    }
+
+    #[test]
+    fn search_gives_up_when_out_of_budget() {
+        let board = Board::from_rows(&["#.", ".#"]);
+        let (rows, columns) = (&board.horizontal_count, &board.vertical_count);
+        assert_eq!(solve_within(rows, columns, 0), Solutions::Undecided);
+        assert!(matches!(
+            solve_within(rows, columns, SEARCH_BUDGET),
+            Solutions::Multiple(..)
+        ));
+        // Line logic alone needs no guesses.
+        let board = Board::from_rows(&["####.", ".....", "#.#.#"]);
+        assert!(matches!(
+            solve_within(&board.horizontal_count, &board.vertical_count, 0),
+            Solutions::Unique(_)
+        ));
+    }
+
+    #[test]
+    fn line_solvable_boards_need_no_guess() {
+        let board = |rows: &[&str]| Board::from_rows(rows);
+        assert!(board(&["####.", ".....", "#.#.#"]).is_line_solvable());
+        let stalling = board(&[".##.", "#..#", "....", "...."]);
+        assert!(!stalling.is_line_solvable());
+        assert!(stalling.has_unique_solution());
+        assert!(!board(&["#.", ".#"]).is_line_solvable());
+        assert!(!board(&["#.", ".#"]).has_unique_solution());
+    }
+
+    #[test]
+    fn solve_line_settles_overlaps_and_unreachable_cells() {
+        assert_eq!(
+            solve_line(&[3], &[None; 5]),
+            Some(vec![None, None, Some(true), None, None])
+        );
+        assert_eq!(
+            solve_line(&[1], &[None, Some(true), None]),
+            Some(vec![Some(false), Some(true), Some(false)])
+        );
+        assert_eq!(solve_line(&[2, 2], &[None; 4]), None);
+        assert_eq!(solve_line(&[], &[Some(true)]), None);
+    }
+}
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Game, GameEvent, Tile, Winstate};
+
+/// What a move changed: each tile it touched as it was before and after, and the
+/// winstate on either side of it.
+#[derive(Clone, Serialize, Deserialize)]
+struct Move {
+    tiles: Vec<(usize, Tile, Tile)>,
+    winstate: (Winstate, Winstate),
+}
+
+/// Moves that can be stepped back, and moves stepped back that can be played again, most
+/// recent last. Saved with the game, so a restored game can still take back its moves.
+#[derive(Default, Serialize, Deserialize)]
+pub struct History {
+    undo: Vec<Move>,
+    redo: Vec<Move>,
+}
+
+impl History {
+    /// Whether stepping back through the moves from `tiles`, and forward through those
+    /// taken back, finds every tile as the moves left it, as it does unless a saved
+    /// game was tampered with.
+    pub(super) fn leads_to(&self, tiles: &[Tile]) -> bool {
+        let fits = |moves: &[Move], backwards: bool| {
+            let mut board = tiles.to_vec();
+            for step in moves.iter().rev() {
+                for &(id, old, new) in &step.tiles {
+                    let (from, to) = if backwards { (new, old) } else { (old, new) };
+                    if board.get(id) != Some(&from) {
+                        return false;
+                    }
+                    board[id] = to;
+                }
+            }
+            true
+        };
+        fits(&self.undo, true) && fits(&self.redo, false)
+    }
+}
+
+impl Game {
+    /// Whether reveals can be stepped back. Timed games that can be lost only allow it for
+    /// marks, so a reveal there is final and clears what came before it.
+    pub fn can_undo_reveals(&self) -> bool {
+        self.config.relaxed || self.config.no_loss
+    }
+    pub fn can_undo(&self) -> bool {
+        !self.paused && !self.history.undo.is_empty()
+    }
+    pub fn can_redo(&self) -> bool {
+        !self.paused && !self.history.redo.is_empty()
+    }
+    /// Makes a move, remembering the tiles it changed if there were any. Nothing is
+    /// remembered once the move wins the game.
+    pub(super) fn undoable(
+        &mut self,
+        make_move: impl FnOnce(&mut Game) -> Vec<GameEvent>,
+    ) -> Vec<GameEvent> {
+        let (tiles, winstate) = (self.board.board_vec.clone(), self.winstate);
+        let events = make_move(self);
+        // Only the tiles that changed are kept, a single one or a line's worth for most
+        // moves, rather than the whole board for every click.
+        let changed: Vec<_> = tiles
+            .into_iter()
+            .zip(&self.board.board_vec)
+            .enumerate()
+            .filter(|(_, (old, new))| old != *new)
+            .map(|(id, (old, &new))| (id, old, new))
+            .collect();
+        if !changed.is_empty() {
+            let revealed = changed
+                .iter()
+                .any(|(_, old, new)| old.hidden && !new.hidden);
+            if revealed && !self.can_undo_reveals() {
+                self.history.undo.clear();
+            } else {
+                self.history.undo.push(Move {
+                    tiles: changed,
+                    winstate: (winstate, self.winstate),
+                });
+            }
+            self.history.redo.clear();
+        }
+        // A win is final, so it can't be taken back and won again for another best time.
+        // Losses stay undoable, that being what undo is for in relaxed games.
+        if let Winstate::Won = self.winstate {
+            self.history = History::default();
+        }
+        events
+    }
+    /// Takes back the last move.
+    pub fn undo(&mut self) {
+        if self.paused {
+            return;
+        }
+        if let Some(last) = self.history.undo.pop() {
+            for &(id, old, _) in &last.tiles {
+                self.board.board_vec[id] = old;
+            }
+            self.set_winstate(last.winstate.0);
+            self.history.redo.push(last);
+        }
+    }
+    /// Plays the last move taken back again.
+    pub fn redo(&mut self) {
+        if self.paused {
+            return;
+        }
+        if let Some(next) = self.history.redo.pop() {
+            for &(id, _, new) in &next.tiles {
+                self.board.board_vec[id] = new;
+            }
+            self.set_winstate(next.winstate.1);
+            self.history.undo.push(next);
+        }
+    }
+    fn set_winstate(&mut self, winstate: Winstate) {
+        self.winstate = winstate;
+        self.clock
+            .set_running(matches!(self.winstate, Winstate::InProgress));
+        // Stepping through moves again is no streak of correct reveals.
+        self.combo = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Assists, Board, GameConfig};
+    use super::*;
+
+    fn game(relaxed: bool) -> Game {
+        let mut config = GameConfig::new(3, 2, 3).unwrap();
+        config.relaxed = relaxed;
+        Game::with_board(config, Board::from_rows(&["##.", "#.."]))
+    }
+
+    #[test]
+    fn marks_are_undone_and_redone() {
+        let mut game = game(false);
+        game.toggle_mark(2);
+        game.toggle_mark(4);
+        game.undo();
+        assert!(game.board.board_vec[2].marked && !game.board.board_vec[4].marked);
+        game.undo();
+        assert!(!game.board.board_vec[2].marked && !game.can_undo());
+        game.redo();
+        game.redo();
+        assert!(game.board.board_vec[2].marked && game.board.board_vec[4].marked);
+        assert!(!game.can_redo());
+    }
+
+    #[test]
+    fn new_moves_drop_what_was_taken_back() {
+        let mut game = game(false);
+        game.toggle_mark(2);
+        game.undo();
+        game.toggle_mark(4);
+        assert!(!game.can_redo());
+    }
+
+    #[test]
+    fn moves_changing_nothing_are_not_remembered() {
+        let mut game = game(true);
+        game.reveal(0);
+        game.reveal(0);
+        game.toggle_mark(0);
+        game.undo();
+        assert!(!game.can_undo() && game.board.board_vec[0].hidden);
+    }
+
+    #[test]
+    fn reveals_are_final_in_timed_games() {
+        let mut game = game(false);
+        game.toggle_mark(2);
+        game.reveal(0);
+        assert!(!game.can_undo());
+        game.toggle_mark(4);
+        game.undo();
+        assert!(!game.can_undo() && !game.board.board_vec[0].hidden);
+    }
+
+    #[test]
+    fn relaxed_games_take_back_losing_reveals() {
+        let mut game = game(true);
+        game.reveal(2);
+        assert!(matches!(game.winstate, Winstate::Lost));
+        game.undo();
+        assert!(matches!(game.winstate, Winstate::InProgress));
+        assert!(game.board.board_vec[2].hidden);
+        game.redo();
+        assert!(matches!(game.winstate, Winstate::Lost));
+    }
+
+    #[test]
+    fn history_must_lead_to_the_board() {
+        let mut game = game(false);
+        game.toggle_mark(2);
+        game.toggle_mark(4);
+        game.undo();
+        assert!(game.integrity_problems().is_empty());
+        game.board.board_vec[2].marked = false;
+        assert!(!game.integrity_problems().is_empty());
+    }
+
+    #[test]
+    fn assists_turned_on_mid_game_are_a_move() {
+        let config = GameConfig::new(3, 3, 3).unwrap();
+        let mut game = Game::with_board(config, Board::from_rows(&["##.", "#..", "..."]));
+        game.toggle_mark(8);
+        game.toggle_mark(8);
+        game.set_assists(Assists {
+            auto_cross_blank_lines: true,
+            ..game.config.assists
+        });
+        assert!(game.board.board_vec[8].marked && game.integrity_problems().is_empty());
+        game.undo();
+        assert!(!game.board.board_vec[8].marked && !game.board.board_vec[6].marked);
+    }
+
+    #[test]
+    fn wins_are_final() {
+        let mut game = game(true);
+        for id in [0, 1, 3] {
+            game.reveal(id);
+        }
+        assert!(matches!(game.winstate, Winstate::Won));
+        assert!(!game.can_undo() && !game.can_redo());
+    }
+}
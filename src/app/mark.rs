@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+/// Smallest and largest mark, in percent of a tile.
+pub const MIN_SIZE: u8 = 20;
+pub const MAX_SIZE: u8 = 100;
+
+/// The character drawn on marked tiles.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MarkGlyph {
+    Cross,
+    Dot,
+    Slash,
+    /// Whatever character the player typed in.
+    Custom,
+}
+
+impl MarkGlyph {
+    pub const ALL: [MarkGlyph; 4] = [
+        MarkGlyph::Cross,
+        MarkGlyph::Dot,
+        MarkGlyph::Slash,
+        MarkGlyph::Custom,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MarkGlyph::Cross => "Cross",
+            MarkGlyph::Dot => "Dot",
+            MarkGlyph::Slash => "Slash",
+            MarkGlyph::Custom => "Custom",
+        }
+    }
+}
+
+/// How marked tiles are drawn.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MarkStyle {
+    pub glyph: MarkGlyph,
+    /// The character used by [`MarkGlyph::Custom`], as typed.
+    pub custom: String,
+    /// Size of the glyph in percent of a tile.
+    pub size: u8,
+}
+
+impl Default for MarkStyle {
+    fn default() -> Self {
+        MarkStyle {
+            glyph: MarkGlyph::Cross,
+            custom: String::new(),
+            size: 50,
+        }
+    }
+}
+
+impl MarkStyle {
+    /// The text drawn on a marked tile. A custom glyph left empty falls back to the cross.
+    pub fn text(&self) -> String {
+        let glyph = match self.glyph {
+            MarkGlyph::Cross => 'X',
+            MarkGlyph::Dot => '•',
+            MarkGlyph::Slash => '/',
+            MarkGlyph::Custom => self.custom.trim().chars().next().unwrap_or('X'),
+        };
+        glyph.to_string()
+    }
+
+    /// Font size of the glyph on tiles of side `tile`.
+    pub fn font_size(&self, tile: u16) -> u16 {
+        tile * u16::from(self.size) / 100
+    }
+}
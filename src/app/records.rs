@@ -0,0 +1,102 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::game::GameConfig;
+use super::storage;
+
+/// How many of the fastest times are kept for each board size.
+pub const KEPT_TIMES: usize = 5;
+
+/// One of the fastest solves of a board size.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct BestTime {
+    /// Time on the clock in milliseconds, hint penalties included.
+    pub millis: u64,
+    /// Hints taken during the solve, free ones included.
+    pub hints: u8,
+}
+
+/// The fastest solves of boards of one size and number of filled tiles.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BestTimes {
+    pub width: usize,
+    pub height: usize,
+    pub filled_count: usize,
+    /// Fastest first.
+    pub times: Vec<BestTime>,
+}
+
+impl BestTimes {
+    pub fn is_for(&self, config: &GameConfig) -> bool {
+        (self.width, self.height, self.filled_count)
+            == (config.width, config.height, config.filled_count)
+    }
+}
+
+/// How best times are kept on disk.
+#[derive(Default, Serialize, Deserialize)]
+struct Records {
+    boards: Vec<BestTimes>,
+}
+
+/// Where best times are kept inside the data directory.
+pub fn path(data_dir: &Path) -> PathBuf {
+    data_dir.join("records.toml")
+}
+
+/// The best times in the file at `path`, or none if it is missing or unreadable.
+pub fn load(path: &Path) -> Vec<BestTimes> {
+    std::fs::read_to_string(path)
+        .map(|contents| parse(Some(contents)).boards)
+        .unwrap_or_default()
+}
+
+/// Adds a win of a game with `config` in `time`, taking `hints`, to the file at `path`.
+///
+/// Returns every board's best times as they are now on disk, with the place the new time
+/// took among those of its board, if it was fast enough to be kept.
+pub fn record(
+    path: &Path,
+    config: &GameConfig,
+    time: Duration,
+    hints: u8,
+) -> Result<(Vec<BestTimes>, Option<usize>), String> {
+    let millis = u64::try_from(time.as_millis()).unwrap_or(u64::MAX);
+    storage::update_locked(path, |contents| {
+        let previous = contents.clone().unwrap_or_default();
+        let mut records: Records = storage::parse_or_keep(path, contents)?;
+        let index = match records.boards.iter().position(|best| best.is_for(config)) {
+            Some(index) => index,
+            None => {
+                records.boards.push(BestTimes {
+                    width: config.width,
+                    height: config.height,
+                    filled_count: config.filled_count,
+                    times: Vec::new(),
+                });
+                records.boards.len() - 1
+            }
+        };
+        let times = &mut records.boards[index].times;
+        let place = times.partition_point(|best| best.millis <= millis);
+        times.insert(place, BestTime { millis, hints });
+        times.truncate(KEPT_TIMES);
+        let place = (place < KEPT_TIMES).then_some(place);
+        records
+            .boards
+            .sort_by_key(|best| (best.width, best.height, best.filled_count));
+        // Rather than emptying the file, keep what was there if the new times can't be
+        // written out.
+        let contents = toml::to_string(&records).unwrap_or(previous);
+        Ok((contents, (records.boards, place)))
+    })
+    .map_err(|error| error.to_string())
+}
+
+fn parse(contents: Option<String>) -> Records {
+    contents
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
@@ -0,0 +1,65 @@
+use std::ops::RangeInclusive;
+
+use chrono::{Datelike, Local};
+
+/// A built-in puzzle that only shows up in the menu during part of the year.
+pub struct SeasonalPuzzle {
+    pub name: &'static str,
+    pub month: u32,
+    pub days: RangeInclusive<u32>,
+    pub rows: &'static [&'static str],
+}
+
+impl SeasonalPuzzle {
+    pub fn is_unlocked_today(&self) -> bool {
+        let today = Local::now().date_naive();
+        today.month() == self.month && self.days.contains(&today.day())
+    }
+}
+
+pub const PUZZLES: &[SeasonalPuzzle] = &[
+    SeasonalPuzzle {
+        name: "Pumpkin",
+        month: 10,
+        days: 1..=31,
+        rows: &[
+            ".....##...",
+            "....##....",
+            "..######..",
+            ".########.",
+            "##.####.##",
+            "#..#..#..#",
+            "##########",
+            "#.#.##.#.#",
+            ".########.",
+            "..######..",
+        ],
+    },
+    SeasonalPuzzle {
+        name: "Snowflake",
+        month: 12,
+        days: 1..=31,
+        rows: &[
+            ".....#.....",
+            "..#..#..#..",
+            "...#.#.#...",
+            "#...###...#",
+            ".#..###..#.",
+            "###########",
+            ".#..###..#.",
+            "#...###...#",
+            "...#.#.#...",
+            "..#..#..#..",
+            ".....#.....",
+        ],
+    },
+];
+
+/// Indices into [`PUZZLES`] of the puzzles available today.
+pub fn unlocked_today() -> impl Iterator<Item = usize> {
+    PUZZLES
+        .iter()
+        .enumerate()
+        .filter(|(_, puzzle)| puzzle.is_unlocked_today())
+        .map(|(index, _)| index)
+}
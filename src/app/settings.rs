@@ -0,0 +1,106 @@
+use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use serde::{Deserialize, Serialize};
+
+use super::game::Assists;
+use super::mark::MarkStyle;
+use super::setup::DEFAULT_BOARD_SIZE;
+use super::widget_colors::BoardColors;
+use super::{CellShape, ClueFont};
+
+pub const SETTINGS_VERSION: u64 = 1;
+
+/// Preferences kept with cosmic-config, so they survive restarts and are shared by every
+/// window of the app.
+#[derive(Clone, CosmicConfigEntry, Debug, Deserialize, PartialEq, Serialize)]
+#[version = 1]
+pub struct Settings {
+    /// The board size last typed into the start menu.
+    pub width: usize,
+    pub height: usize,
+    pub filled_count: usize,
+    pub relaxed: bool,
+    pub hints: u8,
+    pub assists: Assists,
+    pub forgiveness_seconds: u8,
+    pub no_guessing: bool,
+    pub progress_in_title: bool,
+    pub crosshair: bool,
+    pub break_reminders: bool,
+    pub enforce_breaks: bool,
+    pub large_print: bool,
+    pub stuck_nudges: bool,
+    pub save_notification: bool,
+    /// Whether the game in progress is saved at all, on close and every
+    /// `autosave_minutes`.
+    pub autosave: bool,
+    /// Minutes between saves of the game in progress, `0` to save only on close.
+    pub autosave_minutes: u8,
+    /// How many saves are kept, the latest and those before it.
+    pub autosaves_kept: u8,
+    /// Minutes without a move after which a timed game pauses itself, `0` never to.
+    pub idle_pause_minutes: u8,
+    pub idle_pause_notification: bool,
+    /// Minutes of play a day after which the game in progress pauses itself, `0` for no
+    /// limit.
+    pub daily_budget_minutes: u16,
+    pub streamer_mode: bool,
+    pub stream_overlay: bool,
+    /// How opaque the board chrome is, in percent.
+    pub board_opacity: u8,
+    pub clue_font: ClueFont,
+    pub board_colors: BoardColors,
+    pub mark_style: MarkStyle,
+    pub cell_shape: CellShape,
+    pub kid_mode: bool,
+    /// Keeps kid mode on. The app can set this but not clear it, so a parent can only
+    /// lift it by editing the settings.
+    pub kid_mode_locked: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            width: DEFAULT_BOARD_SIZE.0,
+            height: DEFAULT_BOARD_SIZE.1,
+            filled_count: 65,
+            relaxed: false,
+            hints: 3,
+            assists: Assists::default(),
+            forgiveness_seconds: 0,
+            no_guessing: false,
+            progress_in_title: false,
+            crosshair: false,
+            break_reminders: false,
+            enforce_breaks: false,
+            large_print: false,
+            stuck_nudges: true,
+            save_notification: true,
+            autosave: true,
+            autosave_minutes: 5,
+            autosaves_kept: 1,
+            idle_pause_minutes: 10,
+            idle_pause_notification: false,
+            daily_budget_minutes: 0,
+            streamer_mode: false,
+            stream_overlay: false,
+            board_opacity: 100,
+            clue_font: ClueFont::Theme,
+            board_colors: BoardColors::default(),
+            mark_style: MarkStyle::default(),
+            cell_shape: CellShape::Square,
+            kid_mode: false,
+            kid_mode_locked: false,
+        }
+    }
+}
+
+/// The handler settings are read and written through, `None` if cosmic-config is
+/// unavailable, and the settings it holds.
+pub fn load(app_id: &str) -> (Option<cosmic_config::Config>, Settings) {
+    let Ok(handler) = cosmic_config::Config::new(app_id, SETTINGS_VERSION) else {
+        return (None, Settings::default());
+    };
+    // Entries that fail to load keep their defaults.
+    let settings = Settings::get_entry(&handler).unwrap_or_else(|(_errors, settings)| settings);
+    (Some(handler), settings)
+}
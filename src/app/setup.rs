@@ -0,0 +1,376 @@
+use std::path::Path;
+
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::game::{Assists, Board, ConfigError, GameConfig, MAX_FORGIVENESS_SECONDS, MAX_HINTS};
+use super::storage;
+
+/// A named board size the menu inputs can be filled with in one go.
+pub struct Preset {
+    pub name: &'static str,
+    pub width: usize,
+    pub height: usize,
+    pub filled_count: usize,
+}
+
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "Small",
+        width: 5,
+        height: 5,
+        filled_count: 15,
+    },
+    Preset {
+        name: "Medium",
+        width: 10,
+        height: 10,
+        filled_count: 60,
+    },
+    Preset {
+        name: "Large",
+        width: 15,
+        height: 15,
+        filled_count: 135,
+    },
+    Preset {
+        name: "Portrait 15×20 dense",
+        width: 15,
+        height: 20,
+        filled_count: 210,
+    },
+    Preset {
+        name: "Landscape 25×10",
+        width: 25,
+        height: 10,
+        // Sparser boards of this size often have clues the solver can't prove unique.
+        filled_count: 125,
+    },
+];
+
+/// A small board that can't be lost, with no timer and the most helpful assists, for
+/// kid mode.
+pub fn kid_config() -> GameConfig {
+    let side: usize = thread_rng().gen_range(4..=6);
+    let mut config = GameConfig::new(side, side, side * side * 3 / 5)
+        .expect("kid boards stay within the allowed dimensions");
+    config.relaxed = true;
+    config.no_loss = true;
+    config.assists = Assists {
+        auto_cross_blank_lines: true,
+        guard_completed_lines: true,
+        cap_completed_runs: true,
+    };
+    config
+}
+
+/// Menu inputs and rules saved by the player under a name.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedPreset {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub filled_count: usize,
+    pub relaxed: bool,
+    pub hints: u8,
+    pub assists: Assists,
+    pub forgiveness_seconds: u8,
+    #[serde(default)]
+    pub no_guessing: bool,
+}
+
+/// How saved presets are kept on disk.
+#[derive(Default, Serialize, Deserialize)]
+struct SavedPresets {
+    presets: Vec<SavedPreset>,
+}
+
+/// The presets saved in the file at `path`, or none if it is missing.
+///
+/// A file that can't be parsed is copied aside with [`storage::keep_corrupt`] and
+/// reported as an error, so the presets in it aren't lost when new ones are saved.
+pub fn load_saved_presets(path: &Path) -> Result<Vec<SavedPreset>, String> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(format!("Couldn't read the saved presets: {error}")),
+    };
+    match toml::from_str::<SavedPresets>(&contents) {
+        Ok(saved) => Ok(saved.presets),
+        Err(_error) => Err(match storage::keep_corrupt(path) {
+            Ok(()) => String::from(
+                "The saved presets couldn't be read, so they were copied aside to a .corrupt file",
+            ),
+            Err(error) => format!("The saved presets couldn't be read or copied aside: {error}"),
+        }),
+    }
+}
+
+/// Replaces the presets in the file at `path`. A file that can't be parsed is copied
+/// aside first, and left alone if that fails.
+pub fn store_saved_presets(path: &Path, presets: &[SavedPreset]) -> Result<(), String> {
+    let contents = toml::to_string(&SavedPresets {
+        presets: presets.to_vec(),
+    })
+    .map_err(|error| error.to_string())?;
+    storage::update_locked(path, |previous| {
+        storage::parse_or_keep::<SavedPresets>(path, previous)?;
+        Ok((contents, ()))
+    })
+    .map_err(|error| error.to_string())
+}
+
+/// Text inputs of the start menu, turned into a [`GameConfig`] when START is pressed.
+pub struct Menu {
+    pub width_input: String,
+    pub height_input: String,
+    pub filled_count_input: String,
+    /// Either a seed for the board or a share code replacing every other input.
+    pub seed_input: String,
+    /// Carried over between games, so it acts as the default for the next one.
+    pub relaxed: bool,
+    pub hints: u8,
+    pub assists: Assists,
+    pub forgiveness_seconds: u8,
+    pub no_guessing: bool,
+    pub error: Option<ConfigError>,
+    /// Path of a text puzzle file to import.
+    pub import_path: String,
+    pub import_error: Option<String>,
+    /// A sample board generated from the current inputs, if they are valid.
+    pub preview: Option<Board>,
+    /// Seed of the sample board, which Start plays when no seed or share code is typed.
+    pub preview_seed: u64,
+    /// Presets saved by the player, in the order they were saved.
+    pub saved_presets: Vec<SavedPreset>,
+    /// Name the current inputs are saved under as a preset.
+    pub preset_name: String,
+    /// Why loading or saving presets failed last time.
+    pub preset_error: Option<String>,
+    /// Smallest tile side, in pixels, of a board picked to fill the window.
+    pub fit_tile_size: u16,
+}
+
+/// Range of [`Menu::fit_tile_size`].
+pub const MIN_FIT_TILE_SIZE: u16 = 20;
+pub const MAX_FIT_TILE_SIZE: u16 = 100;
+
+/// Width and height the menu suggests before anything is typed.
+pub const DEFAULT_BOARD_SIZE: (usize, usize) = (10, 10);
+
+impl Default for Menu {
+    fn default() -> Self {
+        let mut menu = Menu {
+            width_input: DEFAULT_BOARD_SIZE.0.to_string(),
+            height_input: DEFAULT_BOARD_SIZE.1.to_string(),
+            filled_count_input: String::from("65"),
+            seed_input: String::new(),
+            relaxed: false,
+            hints: 3,
+            assists: Assists::default(),
+            forgiveness_seconds: 0,
+            no_guessing: false,
+            error: None,
+            import_path: String::new(),
+            import_error: None,
+            preview: None,
+            preview_seed: thread_rng().gen(),
+            saved_presets: Vec::new(),
+            preset_name: String::new(),
+            preset_error: None,
+            fit_tile_size: 40,
+        };
+        menu.refresh_preview();
+        menu
+    }
+}
+
+impl Menu {
+    pub fn config(&self) -> Result<GameConfig, ConfigError> {
+        let seed = match parse_seed_input(&self.seed_input)? {
+            Some(SeedInput::ShareCode(config)) => return Ok(config),
+            Some(SeedInput::Seed(seed)) => Some(seed),
+            None => None,
+        };
+        let width = self
+            .width_input
+            .trim()
+            .parse()
+            .map_err(|_| ConfigError::InvalidWidth)?;
+        let height = self
+            .height_input
+            .trim()
+            .parse()
+            .map_err(|_| ConfigError::InvalidHeight)?;
+        let filled_count = self
+            .filled_count_input
+            .trim()
+            .parse()
+            .map_err(|_| ConfigError::InvalidFilledCount)?;
+        let mut config = GameConfig::new(width, height, filled_count)?;
+        config.seed = seed;
+        self.apply_options(&mut config);
+        Ok(config)
+    }
+
+    /// The config for playing a premade board with the options chosen in the menu.
+    pub fn config_for_board(&self, board: &Board) -> Result<GameConfig, ConfigError> {
+        let mut config = GameConfig::new(board.width, board.height, board.filled_count)?;
+        self.apply_options(&mut config);
+        Ok(config)
+    }
+
+    /// A board of random but comfortable size and density, with the options chosen in the menu.
+    pub fn surprise_config(&self) -> GameConfig {
+        let mut rng = thread_rng();
+        let width: usize = rng.gen_range(5..=15);
+        let height: usize = rng.gen_range(5..=15);
+        self.random_density_config(width, height)
+    }
+
+    /// A `width`×`height` board of comfortable random density, with the options chosen
+    /// in the menu.
+    pub fn random_density_config(&self, width: usize, height: usize) -> GameConfig {
+        let filled_count = width * height * thread_rng().gen_range(50..=65_usize) / 100;
+        let mut config = GameConfig::new(width, height, filled_count)
+            .expect("callers keep boards within the allowed dimensions");
+        self.apply_options(&mut config);
+        config
+    }
+
+    fn apply_options(&self, config: &mut GameConfig) {
+        config.relaxed = self.relaxed;
+        config.hints = self.hints.min(MAX_HINTS);
+        config.assists = self.assists;
+        config.forgiveness_seconds = self.forgiveness_seconds.min(MAX_FORGIVENESS_SECONDS);
+        config.no_guessing = self.no_guessing;
+    }
+
+    pub fn apply_preset(&mut self, preset: &Preset) {
+        self.width_input = preset.width.to_string();
+        self.height_input = preset.height.to_string();
+        self.filled_count_input = preset.filled_count.to_string();
+        self.error = None;
+        self.refresh_preview();
+    }
+
+    /// Saves the current inputs and rules under [`Menu::preset_name`], replacing a
+    /// preset of the same name.
+    pub fn save_preset(&mut self) -> Result<(), ConfigError> {
+        let config = self.config()?;
+        let name = self.preset_name.trim().to_string();
+        let preset = SavedPreset {
+            name: if name.is_empty() {
+                format!("{}×{}", config.width, config.height)
+            } else {
+                name
+            },
+            width: config.width,
+            height: config.height,
+            filled_count: config.filled_count,
+            relaxed: config.relaxed,
+            hints: config.hints,
+            assists: config.assists,
+            forgiveness_seconds: config.forgiveness_seconds,
+            no_guessing: config.no_guessing,
+        };
+        match self
+            .saved_presets
+            .iter_mut()
+            .find(|saved| saved.name == preset.name)
+        {
+            Some(saved) => *saved = preset,
+            None => self.saved_presets.push(preset),
+        }
+        self.preset_name.clear();
+        Ok(())
+    }
+
+    pub fn apply_saved_preset(&mut self, index: usize) {
+        let Some(preset) = self.saved_presets.get(index).cloned() else {
+            return;
+        };
+        self.width_input = preset.width.to_string();
+        self.height_input = preset.height.to_string();
+        self.filled_count_input = preset.filled_count.to_string();
+        self.relaxed = preset.relaxed;
+        self.hints = preset.hints.min(MAX_HINTS);
+        self.assists = preset.assists;
+        self.forgiveness_seconds = preset.forgiveness_seconds.min(MAX_FORGIVENESS_SECONDS);
+        self.no_guessing = preset.no_guessing;
+        self.error = None;
+        self.refresh_preview();
+    }
+
+    /// The seed a game with `config` from [`Menu::config`] is generated from, so the
+    /// board played is the one previewed.
+    pub fn start_seed(&self, config: &GameConfig) -> u64 {
+        config.seed.unwrap_or(self.preview_seed)
+    }
+
+    /// Regenerates the sample board for the current inputs, or clears it when they are
+    /// invalid.
+    pub fn refresh_preview(&mut self) {
+        self.preview = self
+            .config()
+            .ok()
+            .map(|config| Board::candidate_for_config(&config, self.start_seed(&config)));
+    }
+
+    /// Previews another board, unless a seed or share code picks it.
+    pub fn shuffle_preview(&mut self) {
+        self.preview_seed = thread_rng().gen();
+        self.refresh_preview();
+    }
+}
+
+pub enum SeedInput {
+    Seed(u64),
+    ShareCode(GameConfig),
+}
+
+/// Reads the seed field of the menu, which accepts either a plain seed or a share code.
+pub fn parse_seed_input(input: &str) -> Result<Option<SeedInput>, ConfigError> {
+    let input = input.trim();
+    if input.is_empty() {
+        Ok(None)
+    } else if let Ok(seed) = input.parse() {
+        Ok(Some(SeedInput::Seed(seed)))
+    } else {
+        GameConfig::from_share_code(input).map(|config| Some(SeedInput::ShareCode(config)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreadable_presets_are_copied_aside() {
+        let dir = std::env::temp_dir().join(format!("picross-presets-{}", std::process::id()));
+        let _result = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("presets.toml");
+        assert!(load_saved_presets(&path).unwrap().is_empty());
+
+        std::fs::write(&path, "presets = [").unwrap();
+        assert!(load_saved_presets(&path).is_err());
+        let kept = std::fs::read_to_string(dir.join("presets.toml.corrupt")).unwrap();
+        assert_eq!(kept, "presets = [");
+    }
+
+    #[test]
+    fn typed_seeds_pick_the_preview() {
+        let mut menu = Menu {
+            seed_input: String::from("42"),
+            ..Menu::default()
+        };
+        menu.shuffle_preview();
+        let config = menu.config().unwrap();
+        assert_eq!(menu.start_seed(&config), 42);
+        let expected = Board::candidate_for_config(&config, 42);
+        let preview = menu.preview.as_ref().unwrap();
+        assert_eq!(preview.horizontal_count, expected.horizontal_count);
+        assert_eq!(preview.vertical_count, expected.vertical_count);
+    }
+}
@@ -0,0 +1,294 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::game::GameConfig;
+use super::storage;
+
+/// Days of play time that are kept, counting back from the latest.
+const PLAY_DAYS_KEPT: i32 = 30;
+
+/// What happened to a game, as far as the statistics are concerned.
+#[derive(Clone, Copy, Debug)]
+pub enum Outcome {
+    Started,
+    /// Won, with the game's config and time if the game was timed.
+    Won(Option<(GameConfig, Duration)>),
+    Lost,
+    /// Left for another game or the menu while still in play.
+    Abandoned,
+    /// Played for a while on a day, numbered as in [`PlayDay::day`].
+    Played(i32, Duration),
+}
+
+/// Counts of every game played, kept across launches.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Statistics {
+    pub started: u32,
+    pub won: u32,
+    pub lost: u32,
+    pub abandoned: u32,
+    /// Wins of timed games, which the average solve time is taken over.
+    pub timed_wins: u32,
+    /// Time of all timed wins together, in milliseconds.
+    pub timed_win_millis: u64,
+    /// Wins in a row. Losing or abandoning a game ends the streak.
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    /// Timed wins of each board size that has any.
+    pub boards: Vec<BoardTotals>,
+    /// Time played on each of the latest days that had any, oldest first.
+    pub play_days: Vec<PlayDay>,
+}
+
+/// Time spent with a game in play on one day.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayDay {
+    /// The day in the local calendar, counted from 1 January of the year 1 like chrono's
+    /// `Datelike::num_days_from_ce`.
+    pub day: i32,
+    /// Time played on it, in milliseconds.
+    pub millis: u64,
+}
+
+/// Timed wins of boards of one size and number of filled tiles.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct BoardTotals {
+    pub width: usize,
+    pub height: usize,
+    pub filled_count: usize,
+    pub timed_wins: u32,
+    /// Time of all of them together, in milliseconds.
+    pub timed_win_millis: u64,
+    /// Time of the fastest, in milliseconds.
+    pub best_millis: u64,
+}
+
+impl BoardTotals {
+    pub fn is_for(&self, config: &GameConfig) -> bool {
+        (self.width, self.height, self.filled_count)
+            == (config.width, config.height, config.filled_count)
+    }
+}
+
+/// How a timed win compares to the earlier timed wins of its board size.
+#[derive(Clone, Copy, Debug)]
+pub struct Comparison {
+    pub time: Duration,
+    pub average: Duration,
+    pub best: Duration,
+}
+
+impl Comparison {
+    /// How much faster than the average the win was, in percent of the average, or how
+    /// much slower when negative.
+    pub fn percent_faster(&self) -> i64 {
+        let (time, average) = (
+            self.time.as_millis() as i64,
+            self.average.as_millis() as i64,
+        );
+        if average == 0 {
+            0
+        } else {
+            (average - time) * 100 / average
+        }
+    }
+}
+
+impl Statistics {
+    pub fn count(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Started => self.started += 1,
+            Outcome::Won(timed) => {
+                self.won += 1;
+                self.current_streak += 1;
+                self.longest_streak = self.longest_streak.max(self.current_streak);
+                if let Some((config, time)) = timed {
+                    let millis = u64::try_from(time.as_millis()).unwrap_or(u64::MAX);
+                    self.timed_wins += 1;
+                    self.timed_win_millis = self.timed_win_millis.saturating_add(millis);
+                    self.count_board_win(&config, millis);
+                }
+            }
+            Outcome::Lost => {
+                self.lost += 1;
+                self.current_streak = 0;
+            }
+            Outcome::Abandoned => {
+                self.abandoned += 1;
+                self.current_streak = 0;
+            }
+            Outcome::Played(day, time) => self.count_play(day, time),
+        }
+    }
+
+    fn count_play(&mut self, day: i32, time: Duration) {
+        let millis = u64::try_from(time.as_millis()).unwrap_or(u64::MAX);
+        match self.play_days.iter_mut().find(|played| played.day == day) {
+            Some(played) => played.millis = played.millis.saturating_add(millis),
+            None => {
+                self.play_days.push(PlayDay { day, millis });
+                // The clock may have been set back a day.
+                self.play_days.sort_by_key(|played| played.day);
+            }
+        }
+        let newest = self.play_days.last().map_or(day, |played| played.day);
+        self.play_days
+            .retain(|played| played.day > newest - PLAY_DAYS_KEPT);
+    }
+
+    /// Time played on `day`, numbered as in [`PlayDay::day`].
+    pub fn played_on(&self, day: i32) -> Duration {
+        self.play_days
+            .iter()
+            .find(|played| played.day == day)
+            .map_or(Duration::ZERO, |played| {
+                Duration::from_millis(played.millis)
+            })
+    }
+
+    fn count_board_win(&mut self, config: &GameConfig, millis: u64) {
+        let totals = match self.boards.iter().position(|totals| totals.is_for(config)) {
+            Some(index) => &mut self.boards[index],
+            None => {
+                self.boards.push(BoardTotals {
+                    width: config.width,
+                    height: config.height,
+                    filled_count: config.filled_count,
+                    best_millis: u64::MAX,
+                    ..BoardTotals::default()
+                });
+                self.boards.last_mut().expect("just pushed")
+            }
+        };
+        totals.timed_wins += 1;
+        totals.timed_win_millis = totals.timed_win_millis.saturating_add(millis);
+        totals.best_millis = totals.best_millis.min(millis);
+    }
+
+    /// Average time of the timed wins, if there are any.
+    pub fn average_solve_time(&self) -> Option<Duration> {
+        (self.timed_wins > 0)
+            .then(|| Duration::from_millis(self.timed_win_millis / u64::from(self.timed_wins)))
+    }
+
+    /// How a timed win of a game with `config` in `time` compares to the timed wins
+    /// counted so far on boards of its size, if there are any.
+    pub fn compare(&self, config: &GameConfig, time: Duration) -> Option<Comparison> {
+        let totals = self
+            .boards
+            .iter()
+            .find(|totals| totals.is_for(config) && totals.timed_wins > 0)?;
+        Some(Comparison {
+            time,
+            average: Duration::from_millis(totals.timed_win_millis / u64::from(totals.timed_wins)),
+            best: Duration::from_millis(totals.best_millis),
+        })
+    }
+}
+
+/// Where the statistics are kept inside the data directory.
+pub fn path(data_dir: &Path) -> PathBuf {
+    data_dir.join("statistics.toml")
+}
+
+/// The statistics in the file at `path`, or all zeros if it is missing or unreadable.
+pub fn load(path: &Path) -> Statistics {
+    parse(std::fs::read_to_string(path).ok())
+}
+
+/// Counts `outcome` in the file at `path`, returning the statistics as they are now on
+/// disk, which includes games counted by other windows.
+pub fn record(path: &Path, outcome: Outcome) -> Result<Statistics, String> {
+    storage::update_locked(path, |contents| {
+        let previous = contents.clone().unwrap_or_default();
+        let mut statistics: Statistics = storage::parse_or_keep(path, contents)?;
+        statistics.count(outcome);
+        let contents = toml::to_string(&statistics).unwrap_or(previous);
+        Ok((contents, statistics))
+    })
+    .map_err(|error| error.to_string())
+}
+
+fn parse(contents: Option<String>) -> Statistics {
+    contents
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streaks_end_with_losses_and_abandoned_games() {
+        let mut statistics = Statistics::default();
+        for outcome in [
+            Outcome::Started,
+            Outcome::Won(None),
+            Outcome::Won(None),
+            Outcome::Lost,
+            Outcome::Won(None),
+            Outcome::Abandoned,
+            Outcome::Won(None),
+        ] {
+            statistics.count(outcome);
+        }
+        let counts = (
+            statistics.started,
+            statistics.won,
+            statistics.lost,
+            statistics.abandoned,
+        );
+        assert_eq!(counts, (1, 4, 1, 1));
+        assert_eq!(statistics.current_streak, 1);
+        assert_eq!(statistics.longest_streak, 2);
+        // Untimed wins have no time to average.
+        assert_eq!(statistics.average_solve_time(), None);
+    }
+
+    #[test]
+    fn wins_compare_to_earlier_ones_of_their_size() {
+        let config = GameConfig::new(10, 10, 65).unwrap();
+        let other = GameConfig::new(5, 5, 15).unwrap();
+        let mut statistics = Statistics::default();
+        for (config, secs) in [(config, 100), (config, 60), (other, 10)] {
+            statistics.count(Outcome::Won(Some((config, Duration::from_secs(secs)))));
+        }
+        statistics.count(Outcome::Won(None));
+        let comparison = statistics
+            .compare(&config, Duration::from_secs(56))
+            .unwrap();
+        assert_eq!(comparison.average, Duration::from_secs(80));
+        assert_eq!(comparison.best, Duration::from_secs(60));
+        assert_eq!(comparison.percent_faster(), 30);
+        let slower = statistics
+            .compare(&config, Duration::from_secs(100))
+            .unwrap();
+        assert_eq!(slower.percent_faster(), -25);
+        assert!(statistics
+            .compare(&GameConfig::new(10, 10, 50).unwrap(), Duration::ZERO)
+            .is_none());
+    }
+
+    #[test]
+    fn play_time_adds_up_by_day() {
+        let mut statistics = Statistics::default();
+        for (day, secs) in [(100, 60), (100, 30), (131, 5), (102, 10)] {
+            statistics.count(Outcome::Played(day, Duration::from_secs(secs)));
+        }
+        assert_eq!(statistics.played_on(102), Duration::from_secs(10));
+        assert_eq!(statistics.played_on(131), Duration::from_secs(5));
+        // A month on, the oldest day is dropped.
+        assert_eq!(statistics.played_on(100), Duration::ZERO);
+        let days: Vec<_> = statistics
+            .play_days
+            .iter()
+            .map(|played| played.day)
+            .collect();
+        assert_eq!(days, [102, 131]);
+        assert_eq!(statistics.won + statistics.started, 0);
+    }
+}
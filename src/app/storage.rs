@@ -0,0 +1,174 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+
+/// Replaces the contents of `path` without ever leaving it half written.
+///
+/// The contents go to a temporary file next to `path` first, which is then renamed over
+/// it, so a crash or a concurrent reader sees either the old file or the new one.
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temporary = sibling(path, &format!("tmp.{}", std::process::id()));
+    let result = File::create(&temporary)
+        .and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temporary, path));
+    if result.is_err() {
+        let _result = fs::remove_file(&temporary);
+    }
+    result
+}
+
+/// Like [`write_atomic`], keeping up to `kept - 1` earlier contents of `path` beside it
+/// as `.1`, `.2` and so on, the most recent first. Copies past those, left from a time
+/// more were kept, are removed.
+pub fn write_rotating(path: &Path, contents: &str, kept: usize) -> io::Result<()> {
+    let mut stale = kept.max(1);
+    while fs::remove_file(sibling(path, &stale.to_string())).is_ok() {
+        stale += 1;
+    }
+    for generation in (1..kept).rev() {
+        let newer = match generation {
+            1 => path.to_path_buf(),
+            _ => sibling(path, &(generation - 1).to_string()),
+        };
+        let older = sibling(path, &generation.to_string());
+        // The newest is copied rather than moved, so `path` is never missing.
+        let result = match generation {
+            1 => fs::copy(&newer, &older).map(|_bytes| ()),
+            _ => fs::rename(&newer, &older),
+        };
+        match result {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+            _ => {}
+        }
+    }
+    write_atomic(path, contents)
+}
+
+/// Rewrites `path` from its current contents while holding a lock on it.
+///
+/// Another window or instance updating the same file waits for the lock, and `merge`
+/// always sees what the last writer left on disk rather than a stale copy, so it can
+/// fold both sets of changes together. Nothing is written if `merge` fails. Returns what
+/// `merge` returned.
+pub fn update_locked<T>(
+    path: &Path,
+    merge: impl FnOnce(Option<String>) -> io::Result<(String, T)>,
+) -> io::Result<T> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // The data file itself is swapped out by the rename, so the lock lives beside it.
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(sibling(path, "lock"))?;
+    lock.lock()?;
+    let current = match fs::read_to_string(path) {
+        Ok(contents) => Some(contents),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        Err(error) => return Err(error),
+    };
+    let (contents, value) = merge(current)?;
+    write_atomic(path, &contents)?;
+    Ok(value)
+}
+
+/// Copies `path` to a `.corrupt` file beside it, so contents that no longer parse
+/// aren't lost when the file is written afresh.
+pub fn keep_corrupt(path: &Path) -> io::Result<()> {
+    fs::copy(path, sibling(path, "corrupt")).map(|_bytes| ())
+}
+
+/// Parses `contents` of the TOML file at `path`, as handed to the merge of
+/// [`update_locked`]. A missing file gives the default, and one that can't be parsed is
+/// set aside with [`keep_corrupt`] first, so rewriting it doesn't lose what it held.
+pub fn parse_or_keep<T: DeserializeOwned + Default>(
+    path: &Path,
+    contents: Option<String>,
+) -> io::Result<T> {
+    match contents.map(|contents| toml::from_str(&contents)) {
+        Some(Ok(value)) => Ok(value),
+        Some(Err(_error)) => keep_corrupt(path).map(|()| T::default()),
+        None => Ok(T::default()),
+    }
+}
+
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    /// An empty directory of its own for the test called `name`.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("picross-{name}-{}", std::process::id()));
+        let _result = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn update_locked_sees_the_last_write() {
+        let path = scratch_dir("update-locked").join("data");
+        let first = update_locked(&path, |old| Ok((String::from("a"), old))).unwrap();
+        let second = update_locked(&path, |old| Ok((String::from("b"), old))).unwrap();
+        assert_eq!((first, second), (None, Some(String::from("a"))));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "b");
+    }
+
+    #[test]
+    fn rotating_writes_keep_earlier_contents() {
+        let path = scratch_dir("write-rotating").join("save");
+        for contents in ["a", "b", "c"] {
+            write_rotating(&path, contents, 3).unwrap();
+        }
+        let read = |generation: &str| fs::read_to_string(sibling(&path, generation)).ok();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "c");
+        assert_eq!((read("1"), read("2")), (Some("b".into()), Some("a".into())));
+        write_rotating(&path, "d", 1).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "d");
+        assert_eq!((read("1"), read("2")), (None, None));
+    }
+
+    #[test]
+    fn failed_merge_writes_nothing() {
+        let path = scratch_dir("failed-merge").join("data");
+        fs::write(&path, "kept").unwrap();
+        let result = update_locked(&path, |_old| {
+            Err::<(String, ()), _>(io::Error::other("merge failed"))
+        });
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "kept");
+    }
+
+    #[test]
+    fn unparseable_files_are_set_aside() {
+        let path = scratch_dir("parse-or-keep").join("data.toml");
+        fs::write(&path, "not [toml").unwrap();
+        let parsed: BTreeMap<String, u32> =
+            parse_or_keep(&path, Some(String::from("not [toml"))).unwrap();
+        assert!(parsed.is_empty());
+        assert_eq!(
+            fs::read_to_string(sibling(&path, "corrupt")).unwrap(),
+            "not [toml"
+        );
+        let missing: BTreeMap<String, u32> = parse_or_keep(&path, None).unwrap();
+        assert!(missing.is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::storage;
+use super::widget_colors::{BoardColors, BoardPart};
+
+/// Board colors saved under a name, kept as one TOML file each in the themes directory.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BoardTheme {
+    pub name: String,
+    pub colors: BoardColors,
+}
+
+/// Where themes are kept inside the data directory.
+pub fn directory(data_dir: &Path) -> PathBuf {
+    data_dir.join("themes")
+}
+
+/// Every readable theme in `dir`, sorted by name. Files that fail to parse are skipped.
+pub fn load_all(dir: &Path) -> Vec<BoardTheme> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut themes: Vec<BoardTheme> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "toml")
+        })
+        .filter_map(|path| parse(&std::fs::read_to_string(path).ok()?).ok())
+        .collect();
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+    themes
+}
+
+/// Saves `theme` into `dir`, replacing a theme of the same name, and returns its path.
+pub fn export(dir: &Path, theme: &BoardTheme) -> Result<PathBuf, String> {
+    let contents = toml::to_string(theme).map_err(|error| error.to_string())?;
+    let path = path_for(dir, &theme.name);
+    storage::write_atomic(&path, &contents).map_err(|error| error.to_string())?;
+    Ok(path)
+}
+
+/// The file in `dir` a theme called `name` is kept in. Names that only differ in what
+/// [`file_stem`] drops, like "My theme" and "My-theme", get numbered files of their own
+/// rather than replacing each other.
+fn path_for(dir: &Path, name: &str) -> PathBuf {
+    let stem = file_stem(name);
+    (1..)
+        .map(|number| match number {
+            1 => dir.join(format!("{stem}.toml")),
+            number => dir.join(format!("{stem}-{number}.toml")),
+        })
+        .find(|path| match std::fs::read_to_string(path) {
+            Ok(contents) => parse(&contents).is_ok_and(|theme| theme.name == name),
+            Err(_error) => !path.exists(),
+        })
+        .expect("some numbered file name is free")
+}
+
+/// Checks the theme file at `source` and copies it into `dir`.
+pub fn import(dir: &Path, source: &Path) -> Result<BoardTheme, String> {
+    let contents = std::fs::read_to_string(source).map_err(|error| error.to_string())?;
+    let theme = parse(&contents)?;
+    export(dir, &theme)?;
+    Ok(theme)
+}
+
+fn parse(contents: &str) -> Result<BoardTheme, String> {
+    let theme: BoardTheme = toml::from_str(contents).map_err(|error| error.to_string())?;
+    if theme.name.trim().is_empty() {
+        return Err(String::from("The theme has no name"));
+    }
+    // An empty color keeps the theme's own, but anything else has to be one.
+    if let Some(part) = BoardPart::ALL.into_iter().find(|&part| {
+        !theme.colors.input(part).trim().is_empty() && theme.colors.color(part).is_none()
+    }) {
+        return Err(format!(
+            "The {} color \"{}\" isn't a hex color like #1e90ff",
+            part.name().to_lowercase(),
+            theme.colors.input(part)
+        ));
+    }
+    Ok(theme)
+}
+
+/// A file name for a theme, keeping letters and digits and dashing everything else.
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if stem.is_empty() {
+        String::from("theme")
+    } else {
+        stem
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::{Duration, Instant};
+
+/// Losses in a row that count as tilting, when each follows the previous one quickly.
+const TILT_LOSSES: usize = 3;
+
+/// How soon after the previous loss another one still counts as part of a quick streak.
+const QUICK_LOSS: Duration = Duration::from_secs(120);
+
+/// How long new games are held back once a break is enforced.
+pub const COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Notices quick streaks of losses and suggests, or enforces, a short break.
+/// Both are off unless turned on.
+#[derive(Debug, Default)]
+pub struct TiltGuard {
+    /// Tells the player to take a breath after a quick streak of losses.
+    pub remind: bool,
+    /// Also holds back new games for [`COOLDOWN`].
+    pub enforce: bool,
+    losses: Vec<Instant>,
+    cooldown_until: Option<Instant>,
+}
+
+impl TiltGuard {
+    /// Counts a lost game, returning whether it completes a quick streak worth a break.
+    pub fn record_loss(&mut self, now: Instant) -> bool {
+        if self
+            .losses
+            .last()
+            .is_some_and(|&previous| now - previous > QUICK_LOSS)
+        {
+            self.losses.clear();
+        }
+        self.losses.push(now);
+        if self.losses.len() < TILT_LOSSES || !(self.remind || self.enforce) {
+            return false;
+        }
+        self.losses.clear();
+        if self.enforce {
+            self.cooldown_until = Some(now + COOLDOWN);
+        }
+        true
+    }
+
+    /// A win breaks the streak.
+    pub fn record_win(&mut self) {
+        self.losses.clear();
+    }
+
+    /// Whether new games are being held back right now.
+    pub fn cooling_down(&self, now: Instant) -> bool {
+        self.cooldown_until.is_some_and(|until| now < until)
+    }
+}
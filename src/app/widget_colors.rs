@@ -1,8 +1,12 @@
-use crate::app::{widget, Theme};
+use crate::app::{theme, widget, Theme};
 use cosmic::{
-    iced::{Border, Color},
+    iced::{
+        gradient::Linear, widget::container::StyleSheet, Background, Border, Color, Gradient,
+        Radians,
+    },
     iced_core::Shadow,
 };
+use serde::{Deserialize, Serialize};
 
 const GREY1RGB: Color = Color {
     r: 238.0 / 255.0,
@@ -28,6 +32,7 @@ const ORANGE1RGB: Color = Color {
 pub fn blacktheme(theme: &Theme) -> widget::container::Appearance {
     let mut appearance = orange1theme(theme);
     appearance.icon_color = Some(Color::BLACK);
+    appearance.text_color = Some(contrasting_text(Color::BLACK));
     appearance.background = Some(cosmic::iced::Background::Color(Color::BLACK));
     appearance
 }
@@ -35,7 +40,7 @@ pub fn blacktheme(theme: &Theme) -> widget::container::Appearance {
 pub fn whitetheme(theme: &Theme) -> widget::container::Appearance {
     let mut appearance = orange1theme(theme);
     appearance.icon_color = Some(Color::WHITE);
-    appearance.text_color = Some(Color::BLACK);
+    appearance.text_color = Some(contrasting_text(Color::WHITE));
     appearance.background = Some(cosmic::iced::Background::Color(Color::WHITE));
     appearance
 }
@@ -44,7 +49,7 @@ pub fn orange1theme(theme: &Theme) -> widget::container::Appearance {
     let cosmic = theme.cosmic();
     widget::container::Appearance {
         icon_color: Some(ORANGE1RGB),
-        text_color: Some(Color::WHITE),
+        text_color: Some(contrasting_text(ORANGE1RGB)),
         background: Some(cosmic::iced::Background::Color(ORANGE1RGB)),
         border: Border {
             color: Color::TRANSPARENT,
@@ -73,6 +78,275 @@ pub fn gray1theme(theme: &Theme) -> widget::container::Appearance {
 
 pub fn gray2theme(theme: &Theme) -> widget::container::Appearance {
     let mut appearance = orange1theme(theme);
+    appearance.text_color = Some(contrasting_text(GREY2RGB));
     appearance.background = Some(cosmic::iced::Background::Color(GREY2RGB));
     appearance
 }
+
+/// A part of the playfield whose color can be picked instead of following the theme.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BoardPart {
+    Background,
+    Clues,
+    Grid,
+    /// The frame streamer mode draws around the playfield.
+    StreamBorder,
+}
+
+impl BoardPart {
+    pub const ALL: [Self; 4] = [
+        Self::Background,
+        Self::Clues,
+        Self::Grid,
+        Self::StreamBorder,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Background => "Board background",
+            Self::Clues => "Clue panels",
+            Self::Grid => "Grid lines",
+            Self::StreamBorder => "Streamer mode border",
+        }
+    }
+}
+
+/// Hex colors typed for each [`BoardPart`], an empty or invalid one keeps the theme's.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoardColors {
+    pub background: String,
+    pub clues: String,
+    pub grid: String,
+    pub stream_border: String,
+}
+
+impl BoardColors {
+    pub fn input(&self, part: BoardPart) -> &str {
+        match part {
+            BoardPart::Background => &self.background,
+            BoardPart::Clues => &self.clues,
+            BoardPart::Grid => &self.grid,
+            BoardPart::StreamBorder => &self.stream_border,
+        }
+    }
+
+    pub fn set_input(&mut self, part: BoardPart, input: String) {
+        match part {
+            BoardPart::Background => self.background = input,
+            BoardPart::Clues => self.clues = input,
+            BoardPart::Grid => self.grid = input,
+            BoardPart::StreamBorder => self.stream_border = input,
+        }
+    }
+
+    pub fn color(&self, part: BoardPart) -> Option<Color> {
+        parse_hex(self.input(part))
+    }
+
+    /// The style of `part`, or `fallback` if no color was picked for it.
+    pub fn style(&self, part: BoardPart, fallback: theme::Container) -> theme::Container {
+        self.color(part).map_or(fallback, solid)
+    }
+}
+
+/// Colors on the board: the ones picked for its parts, then filled and empty tiles.
+pub fn palette(colors: &BoardColors) -> Vec<Color> {
+    BoardPart::ALL
+        .into_iter()
+        .filter_map(|part| colors.color(part))
+        .chain([Color::BLACK, GREY1RGB])
+        .collect()
+}
+
+/// A kind of color blindness the palette can be previewed through.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Deficiency {
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl Deficiency {
+    pub const ALL: [Self; 3] = [Self::Protanopia, Self::Deuteranopia, Self::Tritanopia];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Protanopia => "Protanopia",
+            Self::Deuteranopia => "Deuteranopia",
+            Self::Tritanopia => "Tritanopia",
+        }
+    }
+
+    /// How `color` looks with this deficiency, after Machado, Oliveira and Fernandes (2009)
+    /// at full severity.
+    pub fn simulate(self, color: Color) -> Color {
+        let matrix = match self {
+            Self::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Self::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Self::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        };
+        let linear = [color.r, color.g, color.b].map(to_linear);
+        let [r, g, b] = matrix.map(|row| {
+            let channel = row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2];
+            from_linear(channel.clamp(0.0, 1.0))
+        });
+        Color {
+            r,
+            g,
+            b,
+            a: color.a,
+        }
+    }
+}
+
+fn to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(channel: f32) -> f32 {
+    if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Parses a `#rrggbb` color, the `#` being optional.
+pub fn parse_hex(input: &str) -> Option<Color> {
+    let input = input.trim();
+    let hex = input.strip_prefix('#').unwrap_or(input);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    Some(Color::from_rgb8(
+        (value >> 16) as u8,
+        (value >> 8) as u8,
+        value as u8,
+    ))
+}
+
+/// Relative luminance of `color` as defined by WCAG, from 0 for black to 1 for white.
+pub fn luminance(color: Color) -> f32 {
+    0.2126 * to_linear(color.r) + 0.7152 * to_linear(color.g) + 0.0722 * to_linear(color.b)
+}
+
+/// Black or white, whichever contrasts more with `background`.
+pub fn contrasting_text(background: Color) -> Color {
+    // Where the contrast ratios against black and white are equal.
+    if luminance(background) > 0.179 {
+        Color::BLACK
+    } else {
+        Color::WHITE
+    }
+}
+
+/// A plain container filled with `color`, with text readable on top of it.
+pub fn solid(color: Color) -> theme::Container {
+    theme::Container::custom(move |theme: &Theme| {
+        let mut appearance = orange1theme(theme);
+        appearance.icon_color = Some(contrasting_text(color));
+        appearance.text_color = Some(contrasting_text(color));
+        appearance.background = Some(cosmic::iced::Background::Color(color));
+        appearance
+    })
+}
+
+/// `style` outlined faintly in the accent color, marking the row and column of the
+/// hovered tile.
+pub fn guided(style: theme::Container) -> theme::Container {
+    theme::Container::custom(move |theme: &Theme| {
+        let mut appearance = theme.appearance(&style);
+        appearance.border.color = Color {
+            a: 0.5,
+            ..theme.cosmic().accent_color().into()
+        };
+        appearance.border.width = 1.0;
+        appearance
+    })
+}
+
+/// A transparent container framed `width` thick in `color`, or in the accent color.
+pub fn stream_border(color: Option<Color>, width: f32) -> theme::Container {
+    theme::Container::custom(move |theme: &Theme| {
+        let mut appearance = theme.appearance(&theme::Container::Transparent);
+        appearance.border.color = color.unwrap_or_else(|| theme.cosmic().accent_color().into());
+        appearance.border.width = width;
+        appearance
+    })
+}
+
+/// `style` with a band of the accent color filling `progress` of it, from the left or,
+/// when `upwards`, from the bottom.
+pub fn progress_fill(style: theme::Container, progress: f32, upwards: bool) -> theme::Container {
+    let progress = progress.clamp(0.0, 1.0);
+    theme::Container::custom(move |theme: &Theme| {
+        let mut appearance = theme.appearance(&style);
+        let base = match appearance.background {
+            Some(Background::Color(color)) => color,
+            _ => Color::TRANSPARENT,
+        };
+        let accent: Color = theme.cosmic().accent_color().into();
+        let fill = mix(base, accent, 0.25);
+        let angle = if upwards {
+            Radians(0.0)
+        } else {
+            Radians(std::f32::consts::FRAC_PI_2)
+        };
+        appearance.background = Some(Background::Gradient(Gradient::Linear(
+            Linear::new(angle)
+                .add_stop(0.0, fill)
+                .add_stop(progress, fill)
+                .add_stop(progress, base)
+                .add_stop(1.0, base),
+        )));
+        appearance
+    })
+}
+
+/// `from` moved `amount` of the way towards an opaque `to`.
+fn mix(from: Color, to: Color, amount: f32) -> Color {
+    let channel = |from: f32, to: f32| from + (to - from) * amount;
+    Color {
+        r: channel(from.r, to.r),
+        g: channel(from.g, to.g),
+        b: channel(from.b, to.b),
+        a: channel(from.a, 1.0),
+    }
+}
+
+/// `style` with its background let through `opacity` of the way, so what is behind the
+/// window shows through where the compositor allows it.
+pub fn translucent(style: theme::Container, opacity: f32) -> theme::Container {
+    if opacity >= 1.0 {
+        return style;
+    }
+    let opacity = opacity.max(0.0);
+    theme::Container::custom(move |theme: &Theme| {
+        let mut appearance = theme.appearance(&style);
+        if let Some(Background::Color(color)) = appearance.background {
+            appearance.background = Some(Background::Color(Color {
+                a: color.a * opacity,
+                ..color
+            }));
+        }
+        appearance
+    })
+}
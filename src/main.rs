@@ -1,6 +1,8 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use app::Picross;
+use std::path::PathBuf;
+
+use app::{BoardScale, Flags, Picross, DEFAULT_BOARD_SIZE};
 /// The `app` module is used by convention to indicate the main component of our application.
 mod app;
 mod core;
@@ -8,9 +10,44 @@ mod core;
 /// The `cosmic::app::run()` function is the starting point of your application.
 /// It takes two arguments:
 /// - `settings` is a structure that contains everything relevant with your app's configuration, such as antialiasing, themes, icons, etc...
-/// - `()` is the flags that your app needs to use before it starts.
-///  If your app does not need any flags, you can pass in `()`.
+/// - `flags` holds the command line options, see [`app::Flags`].
 fn main() -> cosmic::iced::Result {
-    let settings = cosmic::app::Settings::default();
-    cosmic::app::run::<Picross>(settings, ())
+    #[cfg(feature = "puffin")]
+    let _puffin_server = {
+        profiling::puffin::set_scopes_on(true);
+        puffin_http::Server::new(&format!("127.0.0.1:{}", puffin_http::DEFAULT_PORT)).ok()
+    };
+
+    let (width, height) = DEFAULT_BOARD_SIZE;
+    // Transparent, so the board can be made to let the desktop through.
+    let settings = cosmic::app::Settings::default()
+        .size(app::window_size_for_board(
+            width,
+            height,
+            BoardScale::NORMAL,
+        ))
+        .transparent(true);
+    cosmic::app::run::<Picross>(settings, flags())
+}
+
+/// Environment variable relocating all persisted state, overridden by `--data-dir`.
+const DATA_DIR_ENV: &str = "COSMIC_EXT_PICROSS_DATA_DIR";
+
+fn flags() -> Flags {
+    let mut args = std::env::args_os().skip(1);
+    let mut data_dir = None;
+    let mut kid_mode = false;
+    while let Some(arg) = args.next() {
+        if arg == "--kid-mode" {
+            kid_mode = true;
+        } else if arg == "--data-dir" {
+            data_dir = args.next().map(PathBuf::from);
+        } else if let Some(path) = arg.to_str().and_then(|arg| arg.strip_prefix("--data-dir=")) {
+            data_dir = Some(PathBuf::from(path));
+        }
+    }
+    Flags {
+        data_dir: data_dir.or_else(|| std::env::var_os(DATA_DIR_ENV).map(PathBuf::from)),
+        kid_mode,
+    }
 }